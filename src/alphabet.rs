@@ -0,0 +1,125 @@
+use std::str::FromStr;
+
+/// symbols used to read and write the numbers of a board, the first symbol stands for 1,
+/// the second for 2 and so on. Empty cells are printed as `.`, while board files write them
+/// as `0` unless it is a symbol (see `Board::grid_text`)
+#[derive(Clone, Debug, PartialEq)]
+pub struct Alphabet {
+    symbols: Vec<char>,
+}
+
+impl Alphabet {
+    pub fn new(symbols: &str) -> Self {
+        Self::try_new(symbols).unwrap()
+    }
+
+    fn try_new(symbols: &str) -> Result<Self, String> {
+        let symbols: Vec<char> = symbols.chars().collect();
+        for (i, c) in symbols.iter().enumerate() {
+            if *c == '.' || c.is_whitespace() {
+                return Err(format!("{:?} cannot be used as a symbol", c));
+            }
+            if symbols[..i].contains(c) {
+                return Err(format!("{} is repeated in the alphabet", c));
+            }
+        }
+        Ok(Alphabet { symbols })
+    }
+
+    /// `1-9` followed by letters, enough for boards up to 25x25, `0` is an empty cell
+    pub fn digits() -> Self {
+        Self::new("123456789ABCDEFGHIJKLMNOP")
+    }
+
+    /// `0-9A-F`, for 16x16 boards
+    pub fn hex() -> Self {
+        Self::new("0123456789ABCDEF")
+    }
+
+    pub fn len(&self) -> usize {
+        self.symbols.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.symbols.is_empty()
+    }
+
+    /// the number written as `symbol`, lowercase letters are accepted in place of uppercase
+    /// ones and `None` is returned for symbols outside the alphabet
+    pub fn value_of(&self, symbol: char) -> Option<u8> {
+        self.symbols
+            .iter()
+            .position(|&c| c == symbol)
+            .or_else(|| {
+                self.symbols
+                    .iter()
+                    .position(|&c| c == symbol.to_ascii_uppercase())
+            })
+            .map(|ix| (ix + 1) as u8)
+    }
+
+    /// the symbol of `value`, `.` for 0
+    pub fn symbol_of(&self, value: u8) -> char {
+        match value {
+            0 => '.',
+            _ => self.symbols[value as usize - 1],
+        }
+    }
+}
+
+impl Default for Alphabet {
+    fn default() -> Self {
+        Self::digits()
+    }
+}
+
+/// either the name of a predefined alphabet (`digits`, `hex`) or the list of symbols
+impl FromStr for Alphabet {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "digits" => Ok(Self::digits()),
+            "hex" => Ok(Self::hex()),
+            _ => Self::try_new(s),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_digits() {
+        let alphabet = Alphabet::digits();
+        assert_eq!(alphabet.value_of('1'), Some(1));
+        assert_eq!(alphabet.value_of('A'), Some(10));
+        assert_eq!(alphabet.value_of('g'), Some(16));
+        assert_eq!(alphabet.value_of('0'), None);
+        assert_eq!(alphabet.value_of('.'), None);
+        assert_eq!(alphabet.symbol_of(0), '.');
+        assert_eq!(alphabet.symbol_of(9), '9');
+        assert_eq!(alphabet.symbol_of(25), 'P');
+    }
+
+    #[test]
+    fn test_hex() {
+        let alphabet = Alphabet::hex();
+        assert_eq!(alphabet.len(), 16);
+        assert_eq!(alphabet.value_of('0'), Some(1));
+        assert_eq!(alphabet.value_of('f'), Some(16));
+        assert_eq!(alphabet.symbol_of(16), 'F');
+    }
+
+    #[test]
+    fn test_from_str() {
+        assert_eq!("hex".parse::<Alphabet>().unwrap(), Alphabet::hex());
+        assert_eq!("digits".parse::<Alphabet>().unwrap(), Alphabet::digits());
+        let words = "WORDPLAYS".parse::<Alphabet>().unwrap();
+        assert_eq!(words.value_of('W'), Some(1));
+        assert_eq!(words.symbol_of(9), 'S');
+        assert!("ABCA".parse::<Alphabet>().is_err());
+        assert!("AB.".parse::<Alphabet>().is_err());
+    }
+}
@@ -0,0 +1,157 @@
+use ndarray::prelude::*;
+
+use crate::Constraint;
+
+/// an arrow, the number in its circle is the sum of the numbers along its cells. Numbers can
+/// repeat along the arrow, unless they share a unit
+#[derive(Clone, Debug, PartialEq)]
+pub struct Arrow {
+    circle: (usize, usize),
+    cells: Vec<(usize, usize)>,
+}
+
+impl Arrow {
+    /// an arrow starting next to `circle` and going through `cells`, each cell must touch
+    /// the previous one, also diagonally
+    pub fn new(circle: (usize, usize), cells: Vec<(usize, usize)>) -> Self {
+        if cells.is_empty() {
+            panic!("an arrow must have at least one cell");
+        }
+        for (i, cell) in cells.iter().enumerate() {
+            if cells[..i].contains(cell) || *cell == circle {
+                panic!("cell {:?} is repeated in the arrow", cell);
+            }
+        }
+        let path: Vec<(usize, usize)> = [circle].into_iter().chain(cells.clone()).collect();
+        for pair in path.windows(2) {
+            let (prev, next) = (pair[0], pair[1]);
+            if prev.0.abs_diff(next.0) > 1 || prev.1.abs_diff(next.1) > 1 {
+                panic!("cells {:?} and {:?} are not adjacent", prev, next);
+            }
+        }
+        Arrow { circle, cells }
+    }
+
+    pub fn circle(&self) -> (usize, usize) {
+        self.circle
+    }
+
+    pub fn cells(&self) -> &[(usize, usize)] {
+        &self.cells
+    }
+
+    /// given the numbers the circle and the cells of the arrow can still hold, keeps for the
+    /// circle the ones within the sums the arrow can reach and for each cell the ones that
+    /// leave the rest of the arrow a sum the circle can hold
+    pub(crate) fn possible_values(
+        &self,
+        circle: &[bool],
+        cells: &[Vec<bool>],
+    ) -> (Vec<bool>, Vec<Vec<bool>>) {
+        let lowest = |cell: &[bool]| cell.iter().position(|&x| x).map_or(0, |ix| ix + 1);
+        let highest = |cell: &[bool]| cell.iter().rposition(|&x| x).map_or(0, |ix| ix + 1);
+        let sum_lowest: usize = cells.iter().map(|cell| lowest(cell)).sum();
+        let sum_highest: usize = cells.iter().map(|cell| highest(cell)).sum();
+        let circle: Vec<bool> = (0..circle.len())
+            .map(|ix| circle[ix] && sum_lowest <= ix + 1 && ix < sum_highest)
+            .collect();
+        let (circle_lowest, circle_highest) = (lowest(&circle), highest(&circle));
+        let cells = cells
+            .iter()
+            .map(|cell| {
+                // the range of the sum of the other cells
+                let others_lowest = sum_lowest - lowest(cell);
+                let others_highest = sum_highest - highest(cell);
+                (0..cell.len())
+                    .map(|ix| {
+                        cell[ix]
+                            && others_lowest + ix < circle_highest
+                            && circle_lowest <= others_highest + ix + 1
+                    })
+                    .collect()
+            })
+            .collect();
+        (circle, cells)
+    }
+}
+
+impl Constraint for Arrow {
+    /// a partially filled arrow is valid if the circle is within the lowest and highest
+    /// sum the arrow can still reach, the empty cells count as 1 and as the highest number
+    fn check(&self, values: &Array2<u8>) -> bool {
+        let size = values.nrows() as u32;
+        let total: u32 = self.cells.iter().map(|&pos| values[pos] as u32).sum();
+        let empty = self.cells.iter().filter(|&&pos| values[pos] == 0).count() as u32;
+        let (lowest, highest) = (total + empty, total + empty * size);
+        match values[self.circle] as u32 {
+            0 => lowest <= size,
+            circle => lowest <= circle && circle <= highest,
+        }
+    }
+
+    fn prune(&self, candidates: &Array2<Vec<bool>>) -> Vec<((usize, usize), Vec<bool>)> {
+        let cells: Vec<Vec<bool>> = self
+            .cells
+            .iter()
+            .map(|&pos| candidates[pos].clone())
+            .collect();
+        let (circle, cells) = self.possible_values(&candidates[self.circle], &cells);
+        let pruned = self.cells.iter().copied().zip(cells);
+        [(self.circle, circle)].into_iter().chain(pruned).collect()
+    }
+
+    fn explain(&self) -> Option<String> {
+        Some("the numbers along the arrow add up to the one in its circle".to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_check() {
+        let arrow = Arrow::new((0, 0), vec![(0, 1), (1, 2)]);
+        let mut values = Array2::from_elem((9, 9), 0_u8);
+        assert!(arrow.check(&values));
+        values[(0, 0)] = 1;
+        assert!(!arrow.check(&values));
+        values[(0, 0)] = 9;
+        values[(0, 1)] = 4;
+        assert!(arrow.check(&values));
+        values[(1, 2)] = 5;
+        assert!(arrow.check(&values));
+        values[(1, 2)] = 6;
+        assert!(!arrow.check(&values));
+        // 10 does not fit any circle
+        values[(0, 0)] = 0;
+        assert!(!arrow.check(&values));
+    }
+
+    #[test]
+    fn test_possible_values() {
+        let arrow = Arrow::new((0, 0), vec![(0, 1), (0, 2)]);
+        let (circle, cells) = arrow.possible_values(&[true; 9], &vec![vec![true; 9]; 2]);
+        // the circle is at least 1 + 1 and the cells at most 9 - 1
+        assert_eq!(circle.iter().position(|&x| x), Some(1));
+        assert_eq!(cells[0].iter().rposition(|&x| x), Some(7));
+
+        // a 3 in the circle leaves 1 and 2 to the cells
+        let mut three = vec![false; 9];
+        three[2] = true;
+        let (circle, cells) = arrow.possible_values(&three, &vec![vec![true; 9]; 2]);
+        assert_eq!(circle, three);
+        for cell in cells {
+            assert_eq!(
+                cell,
+                [true, true, false, false, false, false, false, false, false]
+            );
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "cells (0, 0) and (0, 2) are not adjacent")]
+    fn test_arrow_far_from_circle() {
+        Arrow::new((0, 0), vec![(0, 2), (0, 3)]);
+    }
+}
@@ -0,0 +1,97 @@
+use crate::sum::CellSum;
+
+/// a group of cells of a killer sudoku, their numbers must add up to `sum`
+/// and no number can be repeated inside the cage
+#[derive(Clone, Debug, PartialEq)]
+pub struct Cage {
+    sum: u32,
+    cells: Vec<(usize, usize)>,
+}
+
+impl Cage {
+    pub fn new(sum: u32, cells: Vec<(usize, usize)>) -> Self {
+        if cells.is_empty() {
+            panic!("a cage must have at least one cell");
+        }
+        for (i, cell) in cells.iter().enumerate() {
+            if cells[..i].contains(cell) {
+                panic!("cell {:?} is repeated in the cage", cell);
+            }
+        }
+        Cage { sum, cells }
+    }
+
+    pub fn sum(&self) -> u32 {
+        self.sum
+    }
+
+    pub fn cells(&self) -> &[(usize, usize)] {
+        &self.cells
+    }
+
+    /// the cage as a sum of different numbers, a partially filled cage is valid if its
+    /// numbers are not repeated and can still add up to the sum
+    pub(crate) fn as_sum(&self) -> CellSum {
+        CellSum {
+            sum: self.sum,
+            cells: self.cells.clone(),
+            distinct: true,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Constraint;
+    use ndarray::prelude::*;
+
+    #[test]
+    fn test_check() {
+        let cage = Cage::new(10, vec![(0, 0), (0, 1), (1, 0)]);
+        let mut values = Array2::from_elem((9, 9), 0_u8);
+        assert!(cage.as_sum().check(&values));
+        values[(0, 0)] = 3;
+        values[(0, 1)] = 5;
+        assert!(cage.as_sum().check(&values));
+        values[(1, 0)] = 2;
+        assert!(cage.as_sum().check(&values));
+        values[(1, 0)] = 1;
+        assert!(!cage.as_sum().check(&values));
+        values[(0, 1)] = 3;
+        values[(1, 0)] = 4;
+        assert!(!cage.as_sum().check(&values));
+    }
+
+    #[test]
+    fn test_possible_values() {
+        let cage = Cage::new(4, vec![(0, 0), (0, 1)]);
+        let possible = cage
+            .as_sum()
+            .possible_values(&[vec![true; 9], vec![true; 9]]);
+        let expected = vec![true, false, true, false, false, false, false, false, false];
+        assert_eq!(possible, vec![expected.clone(), expected]);
+
+        // 17 is 8 + 9, and the second cell cannot be 9
+        let cage = Cage::new(17, vec![(0, 0), (0, 1)]);
+        let mut second = vec![true; 9];
+        second[8] = false;
+        let possible = cage.as_sum().possible_values(&[vec![true; 9], second]);
+        assert_eq!(possible[0].iter().position(|&x| x), Some(8));
+        assert_eq!(possible[1].iter().position(|&x| x), Some(7));
+
+        // 24 is only 7 + 8 + 9
+        let cage = Cage::new(24, vec![(0, 0), (0, 1), (0, 2)]);
+        let possible =
+            cage.as_sum()
+                .possible_values(&[vec![true; 9], vec![true; 9], vec![true; 9]]);
+        for cell in possible {
+            assert_eq!(cell.iter().filter(|&&x| x).count(), 3);
+            assert!(cell[6] && cell[7] && cell[8]);
+        }
+
+        let cage = Cage::new(45, (0..9).map(|j| (0, j)).collect());
+        let possible = cage.as_sum().possible_values(&vec![vec![true; 9]; 9]);
+        assert!(possible.iter().all(|cell| cell.iter().all(|&x| x)));
+    }
+}
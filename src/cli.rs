@@ -1,86 +1,590 @@
 use colored::*;
+use ndarray::Array2;
+use std::cmp::Ordering;
 
-use crate::Board;
+use crate::{Board, Dot, Edge, MultiGrid, Parity, Xv};
 
 impl Board {
     pub fn print_simple(&self) {
-        for i in 0..9 {
-            for j in 0..9 {
-                match self.values[(i, j)] {
-                    0 => print!(". "),
-                    _ => print!("{} ", self.values[(i, j)]),
-                }
+        for i in 0..self.size() {
+            for j in 0..self.size() {
+                print!("{} ", self.alphabet.symbol_of(self.values[(i, j)]));
             }
             println!();
         }
     }
 
     pub fn print_complete(&self) {
-        for i in 0..10 {
-            match i {
-                0 => println!("┌───────┬───────┬───────┐"),
-                3 | 6 => println!("├───────┼───────┼───────┤"),
-                // last line
-                9 => {
-                    println!("└───────┴───────┴───────┘");
-                    break;
-                }
-                _ => (),
-            }
-            for j in 0..10 {
-                match j {
-                    0 | 3 | 6 => print!("│ "),
-                    // last column
-                    9 => {
-                        print!("│");
-                        break;
-                    }
-                    _ => (),
+        self.print_boxed(|i, j| {
+            self.alphabet
+                .symbol_of(self.values[(i, j)])
+                .to_string()
+                .normal()
+        });
+        if !self.thermos.is_empty() {
+            self.print_thermos();
+        }
+        if !self.arrows.is_empty() {
+            self.print_arrows();
+        }
+        if !self.cages.is_empty() {
+            self.print_cages();
+        }
+        self.print_constraints();
+    }
+
+    pub fn print_diff(&self, other: &Board) {
+        self.print_boxed(|i, j| {
+            let symbol = self.alphabet.symbol_of(self.values[(i, j)]).to_string();
+            match other.values[(i, j)] {
+                0 => symbol.red().bold(),
+                _ => symbol.normal(),
+            }
+        });
+    }
+
+    /// print the cages of a killer sudoku with their borders, each cage is named by a letter
+    /// written in its cells and the sums of the cages are listed below
+    pub fn print_cages(&self) {
+        let labels: Vec<char> = ('a'..='z').chain('A'..='Z').collect();
+        let borders = Borders {
+            size: self.size(),
+            region: |i, j| self.cage_of(i, j).map_or(0, |cage| cage + 1),
+            outside: None,
+        };
+        borders.print(|i, j| match self.cage_of(i, j) {
+            Some(cage) => labels[cage % labels.len()].to_string().normal(),
+            None => ".".normal(),
+        });
+        for (chunk_ix, chunk) in self.cages.chunks(6).enumerate() {
+            let sums: Vec<String> = chunk
+                .iter()
+                .enumerate()
+                .map(|(i, cage)| {
+                    let label = labels[(chunk_ix * 6 + i) % labels.len()];
+                    format!("{}: {:>2}", label, cage.sum())
+                })
+                .collect();
+            println!("{}", sums.join("   "));
+        }
+    }
+
+    /// print the thermometers over the regions of the board, the bulb of each one is drawn
+    /// as `●` and its other cells as arrows coming from the previous cell
+    pub fn print_thermos(&self) {
+        let paths: Vec<Vec<(usize, usize)>> = self
+            .thermos
+            .iter()
+            .map(|thermo| thermo.cells().to_vec())
+            .collect();
+        self.print_paths('●', &paths);
+    }
+
+    /// print the arrows over the regions of the board, the circle of each one is drawn as `○`
+    /// and the cells of the arrow as arrows coming from the previous cell
+    pub fn print_arrows(&self) {
+        let paths: Vec<Vec<(usize, usize)>> = self
+            .arrows
+            .iter()
+            .map(|arrow| [&[arrow.circle()], arrow.cells()].concat())
+            .collect();
+        self.print_paths('○', &paths);
+    }
+
+    /// print paths of adjacent cells over the regions of the board, the first cell of each
+    /// path is drawn as `start` and the others as arrows coming from the previous cell
+    fn print_paths(&self, start: char, paths: &[Vec<(usize, usize)>]) {
+        let size = self.size();
+        let mut symbols = Array2::from_elem((size, size), '.');
+        for path in paths.iter() {
+            for pair in path.windows(2) {
+                symbols[pair[1]] = step_arrow(pair[0], pair[1]);
+            }
+        }
+        // a cell starting a path and continuing another one is drawn as a start
+        for path in paths.iter() {
+            symbols[path[0]] = start;
+        }
+        let borders = Borders {
+            size,
+            region: |i, j| self.drawn_region((i, j)),
+            outside: None,
+        };
+        borders.print(|i, j| symbols[(i, j)].to_string().normal());
+    }
+
+    /// the clues written outside the grid: the sandwich clues above the columns and at the
+    /// right of the rows, and the little killer clues where their diagonals come from, with
+    /// their arrow towards the grid
+    fn clues(&self) -> Clues {
+        let mut clues = Clues {
+            top: clue_texts(&self.column_sandwiches),
+            right: clue_texts(&self.row_sandwiches),
+            ..Clues::default()
+        };
+        for little_killer in self.little_killers.iter() {
+            let (di, dj) = little_killer.direction().step();
+            let (i, j) = little_killer.start();
+            clues.add(
+                (i as isize - di, j as isize - dj),
+                self.size(),
+                &little_killer.sum().to_string(),
+                little_killer.direction().arrow(),
+            );
+        }
+        clues
+    }
+
+    /// print the explanation of the constraints added to the board, one per line
+    pub fn print_constraints(&self) {
+        for constraint in self.custom_constraints().iter() {
+            if let Some(explanation) = constraint.explain() {
+                println!("{}", explanation);
+            }
+        }
+    }
+
+    /// print the board with the borders of the regions, `cell` formats the symbol of the cell
+    /// in (row, col), cells on the diagonals of a X-Sudoku are underlined and cells in the
+    /// windows of a Hyper Sudoku are in italic. Empty cells of an even/odd sudoku are drawn as
+    /// `□` when even and `◦` when odd, Kropki dots, XV markers and inequality signs between
+    /// their cells and the clues outside the grid (see `clues`)
+    fn print_boxed(&self, cell: impl Fn(usize, usize) -> ColoredString) {
+        let borders = Borders {
+            size: self.size(),
+            region: |i, j| self.drawn_region((i, j)),
+            outside: None,
+        };
+        let window_cells = match self.hyper {
+            true => self.window_cells().concat(),
+            false => Vec::new(),
+        };
+        borders.print_marked(
+            |i, j| {
+                let mut symbol = match (self.values[(i, j)], self.parity_of(i, j)) {
+                    (0, Some(Parity::Even)) => "□".normal(),
+                    (0, Some(Parity::Odd)) => "◦".normal(),
+                    _ => cell(i, j),
+                };
+                if self.diagonal && self.on_diagonal(i, j) {
+                    symbol = symbol.underline();
                 }
-                match self.values[(i, j)] {
-                    0 => print!(". "),
-                    _ => print!("{} ", self.values[(i, j)]),
+                if window_cells.contains(&(i, j)) {
+                    symbol = symbol.italic();
                 }
+                symbol
+            },
+            |edge| self.edge_marker(edge),
+            &self.clues(),
+        );
+    }
+
+    /// the region whose borders are drawn around the cell, a Latin square has none
+    fn drawn_region(&self, pos: (usize, usize)) -> usize {
+        match self.latin_square {
+            true => 0,
+            false => self.regions[pos],
+        }
+    }
+
+    /// the character drawn on the edge between two adjacent cells, inequality signs point to
+    /// the lower number (`^` when it is in the upper cell and `v` when in the lower one) and
+    /// XV markers are drawn as `X` and `V`
+    fn edge_marker(&self, edge: Edge) -> Option<char> {
+        let dot = self.dot_of(edge).map(|dot| match dot {
+            Dot::White => '○',
+            Dot::Black => '●',
+        });
+        let horizontal = edge.0 .0 == edge.1 .0;
+        let sign = self
+            .inequality_of(edge)
+            .map(|ordering| match (ordering, horizontal) {
+                (Ordering::Less, true) => '<',
+                (_, true) => '>',
+                (Ordering::Less, false) => '^',
+                (_, false) => 'v',
+            });
+        let xv = self.xv_of(edge).map(|xv| match xv {
+            Xv::X => 'X',
+            Xv::V => 'V',
+        });
+        dot.or(sign).or(xv)
+    }
+}
+
+impl MultiGrid {
+    /// print the canvas one row per line, the cells outside every grid are left blank
+    pub fn print_simple(&self) {
+        let (rows, cols) = self.canvas_shape();
+        for i in 0..rows {
+            let line: String = (0..cols)
+                .map(|j| format!("{} ", self.symbol_of(i, j)))
+                .collect();
+            println!("{}", line.trim_end());
+        }
+    }
+
+    /// print the canvas with the borders of the regions of every grid
+    pub fn print_complete(&self) {
+        self.print_boxed(|i, j| self.symbol_of(i, j).to_string().normal());
+    }
+
+    /// print the canvas highlighting the numbers that are empty in `other`
+    pub fn print_diff(&self, other: &MultiGrid) {
+        self.print_boxed(|i, j| {
+            let symbol = self.symbol_of(i, j).to_string();
+            match other.value(i, j) {
+                Some(0) => symbol.red().bold(),
+                _ => symbol.normal(),
             }
-            println!();
+        });
+    }
+
+    /// print the canvas, as large as its longer side, with the borders of the regions of the
+    /// first grid covering each cell. `cell` formats the symbol of the cell in (row, col)
+    fn print_boxed(&self, cell: impl Fn(usize, usize) -> ColoredString) {
+        let (rows, cols) = self.canvas_shape();
+        let size = self.grid_size();
+        let borders = Borders {
+            size: rows.max(cols),
+            region: |i, j| match self.views_of(i, j).first() {
+                Some(&(g, pos)) => 1 + g * size + self.grids()[g].drawn_region(pos),
+                None => 0,
+            },
+            outside: Some(0),
+        };
+        borders.print(cell);
+    }
+
+    /// the symbol of a cell of the canvas, a space outside every grid
+    fn symbol_of(&self, row: usize, col: usize) -> char {
+        match self.value(row, col) {
+            Some(value) => self.grids()[0].alphabet.symbol_of(value),
+            None => ' ',
         }
     }
+}
 
-    pub fn print_diff(&self, other: &Board) {
-        for i in 0..10 {
-            match i {
-                0 => println!("┌───────┬───────┬───────┐"),
-                3 | 6 => println!("├───────┼───────┼───────┤"),
-                // last line
-                9 => {
-                    println!("└───────┴───────┴───────┘");
+/// clues written outside the grid, next to each column and row, one per side. Sides without
+/// clues can be left empty
+#[derive(Default)]
+struct Clues {
+    top: Vec<String>,
+    bottom: Vec<String>,
+    left: Vec<String>,
+    right: Vec<String>,
+    /// the clues in the top left, top right, bottom left and bottom right corners
+    corners: [String; 4],
+}
+
+impl Clues {
+    /// add a clue in `pos`, a cell just outside a grid of `size` rows (its row or column is
+    /// -1 or `size`), with `arrow` written between the clue and the grid. Clues in the same
+    /// place are separated by a space
+    fn add(&mut self, (i, j): (isize, isize), size: usize, clue: &str, arrow: char) {
+        let inside = |k: isize| (0..size as isize).contains(&k);
+        let (side, k) = match (inside(i), inside(j)) {
+            (false, true) if i < 0 => (&mut self.top, j as usize),
+            (false, true) => (&mut self.bottom, j as usize),
+            (true, false) if j < 0 => (&mut self.left, i as usize),
+            (true, false) => (&mut self.right, i as usize),
+            _ => {
+                let corner = 2 * usize::from(i >= 0) + usize::from(j >= 0);
+                let text = match j < 0 {
+                    true => format!("{}{}", clue, arrow),
+                    false => format!("{}{}", arrow, clue),
+                };
+                return push_clue(&mut self.corners[corner], &text);
+            }
+        };
+        side.resize(size.max(side.len()), String::new());
+        let text = match i < 0 || j < 0 {
+            true => format!("{}{}", clue, arrow),
+            false => format!("{}{}", arrow, clue),
+        };
+        push_clue(&mut side[k], &text);
+    }
+
+    /// the width of the clues at the left of the grid
+    fn left_width(&self) -> usize {
+        let corners = [&self.corners[0], &self.corners[2]];
+        self.left
+            .iter()
+            .chain(corners)
+            .map(|clue| clue.chars().count())
+            .max()
+            .unwrap_or(0)
+    }
+}
+
+/// add `text` to the clue, after a space if it is not empty
+fn push_clue(clue: &mut String, text: &str) {
+    if !clue.is_empty() {
+        clue.push(' ');
+    }
+    clue.push_str(text);
+}
+
+/// the text of each clue, an empty one for the lines without clue
+fn clue_texts(clues: &[Option<u32>]) -> Vec<String> {
+    clues
+        .iter()
+        .map(|clue| clue.map_or(String::new(), |clue| clue.to_string()))
+        .collect()
+}
+
+/// borders between the cells of different regions of a square grid,
+/// `region` gives the region of the cell in (row, col)
+struct Borders<F: Fn(usize, usize) -> usize> {
+    size: usize,
+    region: F,
+    /// the region of the cells that are not part of the grid (e.g. the corners of a Samurai
+    /// canvas), no border is drawn around them unless they touch another region
+    outside: Option<usize>,
+}
+
+impl<F: Fn(usize, usize) -> usize> Borders<F> {
+    /// print the grid with its borders, `cell` formats the symbol of the cell in (row, col).
+    ///
+    /// Border lines and columns are only drawn where at least a region ends, so the boxes of
+    /// a classic sudoku take one line for each band of boxes, while a jigsaw takes more
+    fn print(&self, cell: impl Fn(usize, usize) -> ColoredString) {
+        self.print_marked(cell, |_| None, &Clues::default());
+    }
+
+    /// same as `print`, but the character given by `marker` is drawn on the edge between two
+    /// adjacent cells. When a marker lays between two rows, a line is left between every
+    /// pair of rows not divided by a border to make room for it. The `clues` of the columns
+    /// are written top to bottom above and below the grid, the ones of the rows at their sides
+    fn print_marked(
+        &self,
+        cell: impl Fn(usize, usize) -> ColoredString,
+        marker: impl Fn(Edge) -> Option<char>,
+        clues: &Clues,
+    ) {
+        let size = self.size;
+        let border_rows: Vec<bool> = (0..=size)
+            .map(|i| (0..size).any(|j| self.above(i, j)))
+            .collect();
+        let border_cols: Vec<bool> = (0..=size)
+            .map(|j| (0..size).any(|i| self.left(i, j)))
+            .collect();
+        let spaced = (1..size).any(|i| (0..size).any(|j| marker(((i - 1, j), (i, j))).is_some()));
+        let left_width = clues.left_width();
+        let margin = |clue: &str| match left_width {
+            0 => String::new(),
+            _ => format!("{:>width$} ", clue, width = left_width),
+        };
+        let corners = [&clues.corners[0], &clues.corners[1]];
+        self.print_column_clues(&clues.top, true, corners, &border_cols, margin);
+        for (i, &border_row) in border_rows.iter().enumerate() {
+            if border_row {
+                print!("{}", margin(""));
+                self.print_line(i, &border_cols, &marker);
+            } else if spaced {
+                print!("{}", margin(""));
+                self.print_gap(i, &border_cols, &marker);
+            }
+            // last line
+            if i == size {
+                break;
+            }
+            print!("{}", margin(clues.left.get(i).map_or("", String::as_str)));
+            for (j, &border_col) in border_cols.iter().enumerate() {
+                if border_col {
+                    match self.left(i, j) {
+                        true => print!("│"),
+                        false => print!(" "),
+                    }
+                }
+                // last column
+                if j == size {
                     break;
                 }
-                _ => (),
-            }
-            for j in 0..10 {
-                match j {
-                    0 | 3 | 6 => print!("│ "),
-                    // last column
-                    9 => {
-                        print!("│");
-                        break;
-                    }
-                    _ => (),
+                if border_col {
+                    print!(" ");
+                }
+                let right = match j + 1 < size {
+                    true => marker(((i, j), (i, j + 1))).unwrap_or(' '),
+                    false => ' ',
+                };
+                print!("{}{}", cell(i, j), right);
+            }
+            match clues.right.get(i) {
+                Some(clue) if !clue.is_empty() => println!(" {}", clue),
+                _ => println!(),
+            }
+        }
+        let corners = [&clues.corners[2], &clues.corners[3]];
+        self.print_column_clues(&clues.bottom, false, corners, &border_cols, margin);
+    }
+
+    /// print the clues of the columns one character per line, next to the grid: aligned to
+    /// the bottom when `above` it and to the top below it. The clues of the left and right
+    /// `corners` are written on the line next to the grid, `margin` formats the left ones
+    fn print_column_clues(
+        &self,
+        clues: &[String],
+        above: bool,
+        corners: [&String; 2],
+        border_cols: &[bool],
+        margin: impl Fn(&str) -> String,
+    ) {
+        let longest = clues.iter().map(|clue| clue.chars().count()).max();
+        let corner_height = usize::from(corners.iter().any(|corner| !corner.is_empty()));
+        let height = longest.unwrap_or(0).max(corner_height);
+        // the width of the rows of the grid, up to its right border
+        let width: usize = (0..self.size)
+            .map(|j| 2 + 2 * usize::from(border_cols[j]))
+            .sum::<usize>()
+            + usize::from(border_cols[self.size]);
+        for line in 0..height {
+            let next_to_grid = match above {
+                true => line == height - 1,
+                false => line == 0,
+            };
+            let mut text = match next_to_grid {
+                true => margin(corners[0]),
+                false => margin(""),
+            };
+            for (j, clue) in clues.iter().enumerate() {
+                if border_cols[j] {
+                    text.push_str("  ");
                 }
-                match other.values[(i, j)] {
-                    0 => print!("{} ", self.values[(i, j)].to_string().as_str().red().bold()),
-                    _ => print!("{} ", self.values[(i, j)]),
+                let skipped = match above {
+                    true => height - clue.chars().count(),
+                    false => 0,
+                };
+                let c = match line >= skipped {
+                    true => clue.chars().nth(line - skipped).unwrap_or(' '),
+                    false => ' ',
+                };
+                text.push(c);
+                text.push(' ');
+            }
+            if next_to_grid && !corners[1].is_empty() {
+                let end = margin("").chars().count() + width;
+                while text.chars().count() < end {
+                    text.push(' ');
                 }
+                text = text.chars().take(end).collect::<String>() + " " + corners[1];
             }
-            println!();
+            println!("{}", text.trim_end());
         }
     }
+
+    /// print the horizontal borders above the `row`-th row (or below the last one)
+    fn print_line(&self, row: usize, border_cols: &[bool], marker: impl Fn(Edge) -> Option<char>) {
+        let size = self.size;
+        let line = |above: bool| if above { '─' } else { ' ' };
+        for (j, &border_col) in border_cols.iter().enumerate() {
+            if border_col {
+                let up = row > 0 && self.left(row - 1, j);
+                let down = row < size && self.left(row, j);
+                let left = j > 0 && self.above(row, j - 1);
+                let right = j < size && self.above(row, j);
+                print!("{}", junction(up, down, left, right));
+            }
+            if j == size {
+                break;
+            }
+            let above = self.above(row, j);
+            if border_col {
+                print!("{}", line(above));
+            }
+            let middle = match row > 0 && row < size {
+                true => marker(((row - 1, j), (row, j))).unwrap_or_else(|| line(above)),
+                false => line(above),
+            };
+            print!("{}{}", middle, line(above));
+        }
+        println!();
+    }
+
+    /// print the line left above the `row`-th row when it is not divided from the previous
+    /// one by a border, with the markers between the two rows
+    fn print_gap(&self, row: usize, border_cols: &[bool], marker: impl Fn(Edge) -> Option<char>) {
+        let size = self.size;
+        for (j, &border_col) in border_cols.iter().enumerate() {
+            if border_col {
+                let up = self.left(row - 1, j);
+                let down = self.left(row, j);
+                print!("{}", junction(up, down, false, false));
+            }
+            if j == size {
+                break;
+            }
+            if border_col {
+                print!(" ");
+            }
+            print!("{} ", marker(((row - 1, j), (row, j))).unwrap_or(' '));
+        }
+        println!();
+    }
+
+    /// whether a border runs at the left of the cell in (row, col),
+    /// `col` can be the size of the grid for the right edge
+    fn left(&self, row: usize, col: usize) -> bool {
+        let before = (col > 0).then(|| (self.region)(row, col - 1));
+        let after = (col < self.size).then(|| (self.region)(row, col));
+        self.inside(before) != self.inside(after)
+    }
+
+    /// whether a border runs above the cell in (row, col),
+    /// `row` can be the size of the grid for the bottom edge
+    fn above(&self, row: usize, col: usize) -> bool {
+        let before = (row > 0).then(|| (self.region)(row - 1, col));
+        let after = (row < self.size).then(|| (self.region)(row, col));
+        self.inside(before) != self.inside(after)
+    }
+
+    /// the region, unless it is outside the grid
+    fn inside(&self, region: Option<usize>) -> Option<usize> {
+        region.filter(|&region| Some(region) != self.outside)
+    }
+}
+
+/// the arrow pointing from a cell to an adjacent one, diagonals included
+fn step_arrow(from: (usize, usize), to: (usize, usize)) -> char {
+    match (to.0.cmp(&from.0), to.1.cmp(&from.1)) {
+        (Ordering::Less, Ordering::Less) => '↖',
+        (Ordering::Less, Ordering::Equal) => '↑',
+        (Ordering::Less, Ordering::Greater) => '↗',
+        (Ordering::Equal, Ordering::Less) => '←',
+        (Ordering::Equal, Ordering::Equal) => '·',
+        (Ordering::Equal, Ordering::Greater) => '→',
+        (Ordering::Greater, Ordering::Less) => '↙',
+        (Ordering::Greater, Ordering::Equal) => '↓',
+        (Ordering::Greater, Ordering::Greater) => '↘',
+    }
+}
+
+/// box drawing character joining the borders going in the given directions
+fn junction(up: bool, down: bool, left: bool, right: bool) -> char {
+    match (up, down, left, right) {
+        (false, false, false, false) => ' ',
+        (true, false, false, false) => '╵',
+        (false, true, false, false) => '╷',
+        (false, false, true, false) => '╴',
+        (false, false, false, true) => '╶',
+        (true, true, false, false) => '│',
+        (false, false, true, true) => '─',
+        (false, true, false, true) => '┌',
+        (false, true, true, false) => '┐',
+        (true, false, false, true) => '└',
+        (true, false, true, false) => '┘',
+        (true, true, false, true) => '├',
+        (true, true, true, false) => '┤',
+        (false, true, true, true) => '┬',
+        (true, false, true, true) => '┴',
+        (true, true, true, true) => '┼',
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::{Alphabet, Direction, LittleKiller};
     use ndarray::prelude::*;
 
     fn partially_filled_board() -> Board {
@@ -120,4 +624,152 @@ mod tests {
         let partial_full_board = partially_filled_board();
         partial_full_board.print_complete();
     }
+
+    #[test]
+    fn test_other_sizes_print_complete() {
+        Board::empty_with_boxes(2, 2).print_complete();
+        Board::from_board_dir("sizes/quiz-06x06-3x2.txt").print_complete();
+        Board::from_board_dir("sizes/quiz-12x12.txt").print_complete();
+        let board = Board::from_board_dir("sizes/quiz-16x16.txt");
+        board.print_complete();
+        board.solve().unwrap().print_diff(&board);
+    }
+
+    #[test]
+    fn test_alphabet_print_complete() {
+        let mut board = Board::from_board_dir("sizes/quiz-16x16.txt");
+        board.set_alphabet(Alphabet::hex());
+        board.print_simple();
+        board.print_complete();
+        board.solve().unwrap().print_diff(&board);
+
+        let mut board = partially_filled_board();
+        board.set_alphabet(Alphabet::new("WORDPLAYS"));
+        board.print_complete();
+    }
+
+    #[test]
+    fn test_diagonal_print_complete() {
+        let board = Board::from_board_dir("variants/quiz-diagonal.txt");
+        board.print_complete();
+        board.solve().unwrap().print_diff(&board);
+    }
+
+    #[test]
+    fn test_hyper_print_complete() {
+        let board = Board::from_board_dir("variants/quiz-hyper.txt");
+        board.print_complete();
+        board.solve().unwrap().print_diff(&board);
+    }
+
+    #[test]
+    fn test_jigsaw_print_complete() {
+        let board = Board::from_board_dir("variants/solution-jigsaw.txt");
+        board.print_complete();
+    }
+
+    #[test]
+    fn test_kropki_print_complete() {
+        let board = Board::from_board_dir("variants/quiz-kropki.txt");
+        board.print_complete();
+        board.solve().unwrap().print_diff(&board);
+    }
+
+    #[test]
+    fn test_thermo_print_complete() {
+        let board = Board::from_board_dir("variants/quiz-thermo.txt");
+        board.print_complete();
+    }
+
+    #[test]
+    fn test_sandwich_print_complete() {
+        let board = Board::from_board_dir("variants/quiz-sandwich.txt");
+        board.print_complete();
+        board.solve().unwrap().print_diff(&board);
+    }
+
+    #[test]
+    fn test_arrow_print_complete() {
+        let board = Board::from_board_dir("variants/quiz-arrow.txt");
+        board.print_complete();
+        assert_eq!(step_arrow((1, 1), (0, 2)), '↗');
+        assert_eq!(step_arrow((1, 1), (1, 0)), '←');
+    }
+
+    #[test]
+    fn test_even_odd_print_complete() {
+        let board = Board::from_board_dir("variants/quiz-even-odd.txt");
+        board.print_complete();
+        board.solve().unwrap().print_diff(&board);
+    }
+
+    #[test]
+    fn test_greater_than_print_complete() {
+        let board = Board::from_board_dir("variants/quiz-greater-than.txt");
+        board.print_complete();
+        assert_eq!(board.edge_marker(((0, 0), (0, 1))), Some('>'));
+        assert_eq!(board.edge_marker(((0, 0), (1, 0))), Some('^'));
+    }
+
+    #[test]
+    fn test_xv_print_complete() {
+        let board = Board::from_board_dir("variants/quiz-xv.txt");
+        board.print_complete();
+        assert_eq!(board.edge_marker(((0, 1), (0, 2))), Some('V'));
+        assert_eq!(board.edge_marker(((1, 1), (1, 2))), Some('X'));
+    }
+
+    #[test]
+    fn test_little_killer_print_complete() {
+        let board = Board::from_board_dir("variants/quiz-little-killer.txt");
+        board.print_complete();
+        board.solve().unwrap().print_diff(&board);
+
+        // the clues are written where their diagonals come from
+        let clues = board.clues();
+        assert_eq!(clues.top[2], "9↙");
+        assert_eq!(clues.left[1], "41↘");
+        assert_eq!(clues.right[6], "↖24 ↙11");
+        assert_eq!(clues.bottom[7], "↖41");
+
+        // the main diagonals come from the corners
+        let mut board = Board::empty_with_boxes(2, 2);
+        board.add_little_killer(LittleKiller::new(10, (0, 0), Direction::DownRight));
+        board.add_little_killer(LittleKiller::new(9, (0, 3), Direction::DownLeft));
+        board.add_little_killer(LittleKiller::new(8, (3, 3), Direction::UpLeft));
+        let clues = board.clues();
+        assert_eq!(clues.corners, ["10↘", "↙9", "", "↖8"]);
+        board.print_complete();
+    }
+
+    #[test]
+    fn test_latin_square_print_complete() {
+        let board = Board::from_board_dir("variants/quiz-latin-square.txt");
+        board.print_complete();
+        board.solve().unwrap().print_diff(&board);
+    }
+
+    #[test]
+    fn test_samurai_print_complete() {
+        let multi_grid = MultiGrid::from_board_dir("variants/quiz-samurai.txt");
+        multi_grid.print_simple();
+        multi_grid.print_complete();
+        multi_grid.solve().unwrap().print_diff(&multi_grid);
+        MultiGrid::with_layout(crate::Layout::Flower, 4).print_complete();
+    }
+
+    #[test]
+    fn test_junction() {
+        assert_eq!(junction(false, true, false, true), '┌');
+        assert_eq!(junction(true, true, true, true), '┼');
+        assert_eq!(junction(true, false, true, true), '┴');
+        assert_eq!(junction(false, false, false, false), ' ');
+    }
+
+    #[test]
+    fn test_killer_print_complete() {
+        let board = Board::from_board_dir("variants/quiz-killer.txt");
+        board.print_complete();
+        board.solve().unwrap().print_diff(&board);
+    }
 }
@@ -0,0 +1,170 @@
+use ndarray::prelude::*;
+
+/// a rule of the puzzle. Every board comes with its units (rows, columns, regions and the
+/// ones of the variants that are on) and more rules can be added with
+/// `Board::add_constraint`, the solver consults all of them while it propagates. Boards can
+/// be sent to other threads, so the constraints must be `Send` and `Sync` too
+pub trait Constraint: Send + Sync {
+    /// whether the numbers of the board follow the rule, empty cells hold 0. A partially
+    /// filled board is valid as long as the rule can still be followed
+    fn check(&self, values: &Array2<u8>) -> bool;
+
+    /// the cells whose candidates are restricted by the rule, with the numbers they can
+    /// still hold. `candidates[(row, col)][n - 1]` tells whether the cell can hold `n` and
+    /// the cells that are left out keep their candidates
+    fn prune(&self, _candidates: &Array2<Vec<bool>>) -> Vec<((usize, usize), Vec<bool>)> {
+        Vec::new()
+    }
+
+    /// the rule described in words
+    fn explain(&self) -> Option<String> {
+        None
+    }
+}
+
+/// cells that cannot repeat a number, such as a row, a column or a region. A unit with as
+/// many cells as the numbers must contain every number once
+#[derive(Clone, Debug, PartialEq)]
+pub struct Unit {
+    name: String,
+    cells: Vec<(usize, usize)>,
+}
+
+impl Unit {
+    pub fn new(name: &str, cells: Vec<(usize, usize)>) -> Self {
+        if cells.is_empty() {
+            panic!("a unit must have at least one cell");
+        }
+        for (i, cell) in cells.iter().enumerate() {
+            if cells[..i].contains(cell) {
+                panic!("cell {:?} is repeated in the unit", cell);
+            }
+        }
+        Unit {
+            name: name.to_string(),
+            cells,
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn cells(&self) -> &[(usize, usize)] {
+        &self.cells
+    }
+}
+
+impl Constraint for Unit {
+    fn check(&self, values: &Array2<u8>) -> bool {
+        let mut seen = Vec::new();
+        for &pos in self.cells.iter() {
+            match values[pos] {
+                0 => (),
+                n if seen.contains(&n) => return false,
+                n => seen.push(n),
+            }
+        }
+        true
+    }
+
+    /// a number that fits a single cell of a full unit goes there (hidden single). The
+    /// numbers of the solved cells are left out, the solver already excludes them from the
+    /// cells sharing a unit with them
+    fn prune(&self, candidates: &Array2<Vec<bool>>) -> Vec<((usize, usize), Vec<bool>)> {
+        let size = candidates[self.cells[0]].len();
+        if self.cells.len() != size {
+            return Vec::new();
+        }
+        let solved = |pos: (usize, usize), n: usize| {
+            candidates[pos][n] && candidates[pos].iter().filter(|&&x| x).count() == 1
+        };
+        let mut pruned = Vec::new();
+        for n in 0..size {
+            if self.cells.iter().any(|&pos| solved(pos, n)) {
+                continue;
+            }
+            let mut holders = self.cells.iter().filter(|&&pos| candidates[pos][n]);
+            if let (Some(&pos), None) = (holders.next(), holders.next()) {
+                pruned.push((pos, (0..size).map(|m| m == n).collect()));
+            }
+        }
+        pruned
+    }
+
+    fn explain(&self) -> Option<String> {
+        Some(format!("{} cannot repeat a number", self.name))
+    }
+}
+
+/// cells a chess move apart cannot hold the same number, such as a knight's move in an
+/// anti-knight sudoku. The solver excludes those numbers along with the ones of the units
+pub(crate) struct ChessMoves {
+    pub(crate) piece: &'static str,
+    pub(crate) moves: [(isize, isize); 8],
+}
+
+impl Constraint for ChessMoves {
+    fn check(&self, values: &Array2<u8>) -> bool {
+        let size = values.nrows() as isize;
+        values.indexed_iter().all(|((i, j), &n)| {
+            n == 0
+                || self.moves.iter().all(|&(di, dj)| {
+                    let (k, l) = (i as isize + di, j as isize + dj);
+                    !(0..size).contains(&k)
+                        || !(0..size).contains(&l)
+                        || values[(k as usize, l as usize)] != n
+                })
+        })
+    }
+
+    fn explain(&self) -> Option<String> {
+        Some(format!(
+            "cells a {}'s move apart cannot hold the same number",
+            self.piece
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_check() {
+        let unit = Unit::new("row 1", (0..4).map(|j| (0, j)).collect());
+        let mut values = Array2::from_elem((4, 4), 0_u8);
+        assert!(unit.check(&values));
+        values[(0, 0)] = 2;
+        values[(0, 3)] = 3;
+        assert!(unit.check(&values));
+        values[(0, 1)] = 2;
+        assert!(!unit.check(&values));
+        assert_eq!(
+            unit.explain(),
+            Some("row 1 cannot repeat a number".to_string())
+        );
+    }
+
+    #[test]
+    fn test_prune() {
+        let unit = Unit::new("row 1", (0..4).map(|j| (0, j)).collect());
+        let mut candidates = Array2::from_elem((4, 4), vec![true; 4]);
+        assert!(unit.prune(&candidates).is_empty());
+
+        // the first cell holds 1 and only the last one can hold 4, the 1 is left to the
+        // solver
+        candidates[(0, 0)] = vec![true, false, false, false];
+        for j in 1..3 {
+            candidates[(0, j)] = vec![true, true, true, false];
+        }
+        let pruned = unit.prune(&candidates);
+        assert_eq!(pruned, vec![((0, 3), vec![false, false, false, true])]);
+    }
+
+    #[test]
+    #[should_panic(expected = "repeated in the unit")]
+    fn test_repeated_cell() {
+        Unit::new("row 1", vec![(0, 0), (0, 0)]);
+    }
+}
@@ -0,0 +1,61 @@
+use std::{fmt, str::FromStr};
+
+/// how hard a puzzle is, from the simplest techniques of the logical solver that solve it
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Difficulty {
+    /// the numbers already in the peers of a cell leave it a single number (naked singles)
+    Easy,
+    /// every rule of the board prunes the candidates, e.g. a number fitting a single cell of
+    /// a unit goes there (hidden singles)
+    Medium,
+    /// a number locked in the cells two units share is excluded from the rest of them, and
+    /// two cells of a unit with the same two candidates exclude them from the other cells
+    Hard,
+    /// no technique is enough, some numbers have to be guessed
+    Expert,
+}
+
+impl Difficulty {
+    pub const ALL: [Difficulty; 4] = [
+        Difficulty::Easy,
+        Difficulty::Medium,
+        Difficulty::Hard,
+        Difficulty::Expert,
+    ];
+}
+
+impl FromStr for Difficulty {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Difficulty::ALL
+            .into_iter()
+            .find(|difficulty| difficulty.to_string() == s.to_lowercase())
+            .ok_or_else(|| format!("{} is not a difficulty", s))
+    }
+}
+
+impl fmt::Display for Difficulty {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            Difficulty::Easy => "easy",
+            Difficulty::Medium => "medium",
+            Difficulty::Hard => "hard",
+            Difficulty::Expert => "expert",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_str() {
+        assert_eq!("hard".parse(), Ok(Difficulty::Hard));
+        assert_eq!("Easy".parse(), Ok(Difficulty::Easy));
+        assert!("impossible".parse::<Difficulty>().is_err());
+        assert!(Difficulty::Medium < Difficulty::Expert);
+    }
+}
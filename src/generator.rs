@@ -0,0 +1,647 @@
+use rand::{seq::SliceRandom, Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+use std::{
+    cmp::Ordering,
+    convert::Infallible,
+    fmt,
+    str::FromStr,
+    time::{Duration, Instant},
+};
+
+use crate::{Board, Difficulty, Symmetry};
+
+/// the seed a puzzle is generated from, the same seed gives the same puzzle on any machine.
+/// It is written as a number or as any text, which is hashed into a number
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Seed(u64);
+
+impl Seed {
+    pub fn new(value: u64) -> Self {
+        Seed(value)
+    }
+
+    /// a seed drawn at random, to be written along with the puzzle
+    pub fn random() -> Self {
+        Seed(rand::thread_rng().gen())
+    }
+
+    pub fn value(&self) -> u64 {
+        self.0
+    }
+
+    /// the seed `offset` places after this one, e.g. for each puzzle of a series
+    pub fn offset(&self, offset: u64) -> Self {
+        Seed(self.0.wrapping_add(offset))
+    }
+
+    /// the random numbers of the seed, ChaCha8 gives the same ones on every platform
+    fn rng(&self) -> ChaCha8Rng {
+        ChaCha8Rng::seed_from_u64(self.0)
+    }
+}
+
+impl From<u64> for Seed {
+    fn from(value: u64) -> Self {
+        Seed(value)
+    }
+}
+
+/// a number is read as it is, any other text is hashed with FNV-1a
+impl FromStr for Seed {
+    type Err = Infallible;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if let Ok(value) = s.parse() {
+            return Ok(Seed(value));
+        }
+        let hash = s.bytes().fold(0xcbf29ce484222325_u64, |hash, byte| {
+            (hash ^ byte as u64).wrapping_mul(0x100000001b3)
+        });
+        Ok(Seed(hash))
+    }
+}
+
+impl fmt::Display for Seed {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+/// why no puzzle was found with its givens in the cells of a mask
+#[derive(Debug, Clone, PartialEq)]
+pub enum MaskError {
+    /// the mask is empty or none of its cells holds a number
+    NoCells,
+    /// the mask is not as large as the board
+    WrongSize { rows: usize, expected: usize },
+    /// a given of the board is in a cell left out by the mask, numbered from 1
+    GivenOutsideMask { row: usize, col: usize },
+    /// the board has no solution to take the givens from
+    Unsolvable,
+    /// the mask has fewer cells than any puzzle of the board with a unique solution
+    TooFewCells { cells: usize, needed: usize },
+    /// every way of filling the cells of the mask leaves more than one solution
+    NoUniquePuzzle,
+    /// none of the partly filled masks tried before the time ran out led to a unique puzzle
+    TimeIsUp { attempts: u64 },
+}
+
+impl fmt::Display for MaskError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            MaskError::NoCells => write!(f, "the mask has no cells"),
+            MaskError::WrongSize { rows, expected } => {
+                write!(f, "the mask has {} rows instead of {}", rows, expected)
+            }
+            MaskError::GivenOutsideMask { row, col } => {
+                write!(f, "the given in r{}c{} is outside the mask", row, col)
+            }
+            MaskError::Unsolvable => write!(f, "the board has no solution"),
+            MaskError::TooFewCells { cells, needed } => write!(
+                f,
+                "the mask has {} cells and a puzzle of the board needs at least {}",
+                cells, needed
+            ),
+            MaskError::NoUniquePuzzle => {
+                write!(f, "no numbers in its cells give a unique solution")
+            }
+            MaskError::TimeIsUp { attempts } => write!(
+                f,
+                "none of the {} partly filled masks tried led to a unique solution in time",
+                attempts
+            ),
+        }
+    }
+}
+
+impl std::error::Error for MaskError {}
+
+/// the solutions listed for each partly filled mask. Below it the list is complete and
+/// tells at once whether the rest of the mask can give a unique solution
+const SOLUTION_LIMIT: usize = 64;
+
+/// the partly filled masks the first run of the search tries before starting over, each
+/// run tries twice as many as the previous one
+const FIRST_RUN_LENGTH: u64 = 30;
+
+/// the backtracking search of `Board::generate_with_mask`
+struct MaskSearch<R> {
+    /// the cells of the mask without a given, the ones before the current depth are filled
+    cells: Vec<(usize, usize)>,
+    rng: R,
+    start: Instant,
+    budget: Duration,
+    /// the partly filled masks tried so far
+    attempts: u64,
+    /// the partly filled masks the current run can still try
+    left: u64,
+    timed_out: bool,
+}
+
+impl<R: Rng> MaskSearch<R> {
+    /// a puzzle with a unique solution made of `puzzle` and numbers in the cells from the
+    /// `next` one on, `solutions` are the ones of `puzzle` up to `SOLUTION_LIMIT`. `None`
+    /// when there is none, when the run is over or when the time is up
+    fn fill(&mut self, puzzle: &Board, solutions: &[Board], next: usize) -> Option<Board> {
+        if self.start.elapsed() >= self.budget {
+            self.timed_out = true;
+            return None;
+        }
+        let rest = &self.cells[next..];
+        if solutions.len() < SOLUTION_LIMIT {
+            // the rest of the mask can only tell apart the solutions that differ in its cells
+            let unique: Vec<&Board> = solutions
+                .iter()
+                .filter(|solution| {
+                    let same = |other: &&Board| {
+                        rest.iter()
+                            .all(|&pos| other.values[pos] == solution.values[pos])
+                    };
+                    solutions.iter().filter(same).count() == 1
+                })
+                .collect();
+            let solution = unique.choose(&mut self.rng)?;
+            let mut puzzle = puzzle.clone();
+            for &pos in rest.iter() {
+                puzzle.values[pos] = solution.values[pos];
+            }
+            return Some(puzzle);
+        }
+        if rest.is_empty() || self.left == 0 {
+            return None;
+        }
+        // the rest of the mask filled from one of the solutions may already be enough
+        let mut completed = puzzle.clone();
+        let solution = solutions.choose(&mut self.rng)?;
+        for &pos in rest.iter() {
+            completed.values[pos] = solution.values[pos];
+        }
+        if completed.count_solutions(2) == 1 {
+            return Some(completed);
+        }
+        self.attempts += 1;
+        self.left -= 1;
+        // the cell whose number varies the most among the solutions is filled first
+        let size = puzzle.size();
+        let spread = |pos: (usize, usize)| {
+            let mut held = vec![false; size + 1];
+            for solution in solutions {
+                held[solution.values[pos] as usize] = true;
+            }
+            held.into_iter().filter(|&x| x).count()
+        };
+        let first = (next..self.cells.len())
+            .max_by_key(|&k| spread(self.cells[k]))
+            .unwrap();
+        self.cells.swap(next, first);
+        let pos = self.cells[next];
+        // the numbers held by the fewest solutions are tried first, the ones held by none last
+        let mut numbers: Vec<(usize, u8)> = (1..=size as u8)
+            .map(|n| {
+                let holders = solutions.iter().filter(|s| s.values[pos] == n).count();
+                (if holders == 0 { usize::MAX } else { holders }, n)
+            })
+            .collect();
+        numbers.shuffle(&mut self.rng);
+        numbers.sort_by_key(|&(holders, _)| holders);
+        for (_, n) in numbers {
+            let mut child = puzzle.clone();
+            child.values[pos] = n;
+            let solutions = child.solutions(SOLUTION_LIMIT);
+            if solutions.is_empty() {
+                continue;
+            }
+            let found = self.fill(&child, &solutions, next + 1);
+            if found.is_some() || self.timed_out || self.left == 0 {
+                return found;
+            }
+        }
+        None
+    }
+}
+
+impl Board {
+    /// a random puzzle following the rules of the board, whose solution is unique. The givens
+    /// of the board are kept and the ones added are removed one by one, in random order,
+    /// as long as the solution stays unique
+    pub fn generate(&self) -> Board {
+        self.generate_with(&mut rand::thread_rng())
+    }
+
+    /// same as `generate`, the same seed always gives the same puzzle
+    pub fn generate_with_seed(&self, seed: Seed) -> Board {
+        self.generate_with(&mut seed.rng())
+    }
+
+    /// same as `generate`, with the random numbers drawn from `rng`
+    pub fn generate_with<R: Rng>(&self, rng: &mut R) -> Board {
+        self.generate_from(rng, None)
+    }
+
+    /// same as `generate_with_seed`, but the givens added have `symmetry`: they are removed
+    /// together with their images
+    pub fn generate_symmetric(&self, symmetry: Symmetry, seed: Seed) -> Board {
+        self.generate_from(&mut seed.rng(), Some(symmetry))
+    }
+
+    /// a random puzzle drawn from `rng`, whose givens are removed one orbit of the symmetry at
+    /// a time
+    fn generate_from<R: Rng>(&self, rng: &mut R, symmetry: Option<Symmetry>) -> Board {
+        let mut puzzle = self
+            .random_solution(rng)
+            .expect("the board has no solution to generate a puzzle from");
+        let mut orbits = self.empty_orbits(symmetry);
+        orbits.shuffle(rng);
+        for orbit in orbits {
+            let values: Vec<u8> = orbit.iter().map(|&pos| puzzle.values[pos]).collect();
+            for &pos in orbit.iter() {
+                puzzle.values[pos] = 0;
+            }
+            if puzzle.count_solutions(2) != 1 {
+                for (&pos, value) in orbit.iter().zip(values) {
+                    puzzle.values[pos] = value;
+                }
+            }
+        }
+        puzzle
+    }
+
+    /// a puzzle rated `difficulty`, generated from `seed` or, when its puzzle has another
+    /// difficulty, from the seeds after it. Returns the puzzle with the seed it comes from, the
+    /// same seed and difficulty give it again, or `None` when no puzzle is found within `budget`.
+    /// The givens added have `symmetry`, when there is one
+    pub fn generate_with_difficulty(
+        &self,
+        difficulty: Difficulty,
+        symmetry: Option<Symmetry>,
+        seed: Seed,
+        budget: Duration,
+    ) -> Option<(Board, Seed)> {
+        let start = Instant::now();
+        let mut attempt = 0;
+        while start.elapsed() < budget {
+            let seed = seed.offset(attempt);
+            if let Some(puzzle) = self.generate_graded(difficulty, symmetry, &mut seed.rng()) {
+                return Some((puzzle, seed));
+            }
+            attempt += 1;
+        }
+        None
+    }
+
+    /// a puzzle whose givens are exactly the cells of `mask` holding a number, the givens of
+    /// the board must be among them. The cells of the mask are filled one at a time, first
+    /// the one whose number varies the most among the solutions so far, with the numbers the
+    /// fewest solutions hold first. Once the solutions are few enough to be listed, one of
+    /// them that no other matches in the rest of the mask gives the puzzle, or the search
+    /// backtracks. A run trying too many partly filled masks starts over with other random
+    /// choices, each run tries twice as many. Returns the puzzle with its seed, the same seed
+    /// gives the same puzzle, or an error when the mask does not fit the board, has too few
+    /// cells (see `min_givens`), cannot give a unique solution or when `budget` runs out
+    pub fn generate_with_mask(
+        &self,
+        mask: &Board,
+        seed: Seed,
+        budget: Duration,
+    ) -> Result<(Board, Seed), MaskError> {
+        let cells = mask.values.iter().filter(|&&value| value > 0).count();
+        if cells == 0 {
+            return Err(MaskError::NoCells);
+        }
+        if mask.size() != self.size() {
+            return Err(MaskError::WrongSize {
+                rows: mask.size(),
+                expected: self.size(),
+            });
+        }
+        for ((row, col), &value) in self.values.indexed_iter() {
+            if value > 0 && mask.values[(row, col)] == 0 {
+                return Err(MaskError::GivenOutsideMask {
+                    row: row + 1,
+                    col: col + 1,
+                });
+            }
+        }
+        let needed = self.min_givens();
+        if cells < needed {
+            return Err(MaskError::TooFewCells { cells, needed });
+        }
+        let solutions = self.solutions(SOLUTION_LIMIT);
+        if solutions.is_empty() {
+            return Err(MaskError::Unsolvable);
+        }
+        let mut rng = seed.rng();
+        let mut cells: Vec<(usize, usize)> = mask
+            .values
+            .indexed_iter()
+            .filter(|&(pos, &value)| value > 0 && self.values[pos] == 0)
+            .map(|(pos, _)| pos)
+            .collect();
+        cells.shuffle(&mut rng);
+        let mut search = MaskSearch {
+            cells,
+            rng,
+            start: Instant::now(),
+            budget,
+            attempts: 0,
+            left: 0,
+            timed_out: false,
+        };
+        let mut run_length = FIRST_RUN_LENGTH;
+        loop {
+            search.left = run_length;
+            if let Some(puzzle) = search.fill(self, &solutions, 0) {
+                return Ok((puzzle, seed));
+            }
+            if search.timed_out {
+                return Err(MaskError::TimeIsUp {
+                    attempts: search.attempts,
+                });
+            }
+            if search.left > 0 {
+                // the run went through every way of filling the mask
+                return Err(MaskError::NoUniquePuzzle);
+            }
+            run_length = run_length.saturating_mul(2);
+        }
+    }
+
+    /// the fewest givens a puzzle of the board needs to have a unique solution, as far as it
+    /// is easy to tell. Rules made only of units and chess moves cannot tell two numbers
+    /// apart, two numbers missing from the givens could be swapped, and a classic sudoku is
+    /// known to need 17 givens. Boards with clues (cages, thermometers, ...) may need none
+    pub fn min_givens(&self) -> usize {
+        let size = self.size();
+        let clues = !self.relations().is_empty()
+            || !self.sums().is_empty()
+            || !self.thermos.is_empty()
+            || !self.arrows.is_empty()
+            || self.row_sandwiches.iter().any(Option::is_some)
+            || self.column_sandwiches.iter().any(Option::is_some)
+            || self.parities.iter().any(Option::is_some)
+            || !self.custom_constraints.is_empty();
+        let classic = size == 9
+            && self.box_shape() == Some((3, 3))
+            && !self.latin_square
+            && !self.diagonal
+            && !self.hyper
+            && self.extra_regions.is_empty()
+            && !self.anti_knight
+            && !self.anti_king;
+        match (clues, classic) {
+            (true, _) => 0,
+            (false, true) => 17,
+            (false, false) => size.saturating_sub(1),
+        }
+    }
+
+    /// the cells holding a number written as `1` and the empty ones as `.`, with the rows
+    /// separated by `/`, e.g. to record the mask of a puzzle
+    pub fn givens_pattern(&self) -> String {
+        let rows: Vec<String> = self
+            .values
+            .rows()
+            .into_iter()
+            .map(|row| {
+                row.iter()
+                    .map(|&value| if value > 0 { '1' } else { '.' })
+                    .collect()
+            })
+            .collect();
+        rows.join("/")
+    }
+
+    /// a puzzle rated `difficulty` drawn from `rng`: a puzzle that is too hard gets back the
+    /// givens of its solution, one orbit at a time in random order, until it is easy enough.
+    /// `None` when the puzzle is too easy, or when a given makes it easier than `difficulty`
+    fn generate_graded<R: Rng>(
+        &self,
+        difficulty: Difficulty,
+        symmetry: Option<Symmetry>,
+        rng: &mut R,
+    ) -> Option<Board> {
+        let mut puzzle = self.generate_from(rng, symmetry);
+        let solution = puzzle.solve().unwrap();
+        let mut orbits = puzzle.empty_orbits(symmetry);
+        orbits.shuffle(rng);
+        let mut orbits = orbits.into_iter();
+        loop {
+            match puzzle.difficulty().cmp(&difficulty) {
+                Ordering::Equal => return Some(puzzle),
+                Ordering::Less => return None,
+                Ordering::Greater => {
+                    for pos in orbits.next()? {
+                        puzzle.values[pos] = solution.values[pos];
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Cage;
+
+    #[test]
+    fn test_generate() {
+        let puzzle = Board::empty().generate();
+        assert_eq!(puzzle.count_solutions(2), 1);
+        let solution = puzzle.solve().unwrap();
+        assert!(solution.check_complete());
+        // no given can be removed
+        for (pos, &value) in puzzle.values.indexed_iter().filter(|(_, &value)| value > 0) {
+            let mut removed = puzzle.clone();
+            removed.values[pos] = 0;
+            assert!(
+                removed.count_solutions(2) > 1,
+                "{} in {:?} is not needed",
+                value,
+                pos
+            );
+        }
+    }
+
+    #[test]
+    fn test_generate_from_template() {
+        // the givens and the rules of the board are kept
+        let mut template = Board::empty_with_boxes(2, 3);
+        template.set_diagonal(true);
+        template.values[(0, 0)] = 6;
+        let puzzle = template.generate();
+        assert_eq!(puzzle.values[(0, 0)], 6);
+        assert!(puzzle.is_diagonal());
+        assert_eq!(puzzle.count_solutions(2), 1);
+
+        let puzzle = Board::empty_latin_square(5).generate();
+        assert_eq!(puzzle.count_solutions(2), 1);
+    }
+
+    #[test]
+    fn test_seed() {
+        assert_eq!("42".parse::<Seed>().unwrap(), Seed::new(42));
+        assert_eq!("".parse::<Seed>().unwrap().value(), 0xcbf29ce484222325);
+        assert_eq!("a".parse::<Seed>().unwrap().value(), 0xaf63dc4c8601ec8c);
+        assert_eq!(Seed::new(u64::MAX).offset(2), Seed::new(1));
+        assert_eq!(Seed::new(7).to_string(), "7");
+    }
+
+    #[test]
+    fn test_generate_with_seed() {
+        let template = Board::empty_with_boxes(2, 2);
+        let puzzle = template.generate_with_seed(Seed::new(42));
+        assert_eq!(puzzle.grid_text(), GENERATED_42);
+        let seed = "daily".parse().unwrap();
+        assert_eq!(
+            template.generate_with_seed(seed).values,
+            template.generate_with_seed(seed).values
+        );
+    }
+
+    /// the 4x4 puzzle of seed 42, it must never change
+    const GENERATED_42: &str = "0040\n0300\n0004\n2000\n";
+
+    #[test]
+    fn test_generate_with_difficulty() {
+        let template = Board::empty_with_boxes(2, 3);
+        for difficulty in [Difficulty::Easy, Difficulty::Medium] {
+            let budget = Duration::from_secs(60);
+            let (puzzle, seed) = template
+                .generate_with_difficulty(difficulty, None, Seed::new(1), budget)
+                .unwrap();
+            assert_eq!(puzzle.difficulty(), difficulty);
+            assert_eq!(puzzle.count_solutions(2), 1);
+            // the seed gives the puzzle at once
+            let (again, _) = template
+                .generate_with_difficulty(difficulty, None, seed, Duration::from_millis(1))
+                .unwrap();
+            assert_eq!(again.values, puzzle.values);
+        }
+        let budget = Duration::ZERO;
+        assert!(template
+            .generate_with_difficulty(Difficulty::Easy, None, Seed::new(1), budget)
+            .is_none());
+    }
+
+    #[test]
+    fn test_generate_symmetric() {
+        for symmetry in Symmetry::ALL {
+            let puzzle = Board::empty_with_boxes(2, 3).generate_symmetric(symmetry, Seed::new(3));
+            assert_eq!(puzzle.count_solutions(2), 1);
+            assert!(puzzle.symmetries().contains(&symmetry), "{}", symmetry);
+        }
+        let budget = Duration::from_secs(60);
+        let (puzzle, _) = Board::empty_with_boxes(2, 3)
+            .generate_with_difficulty(
+                Difficulty::Medium,
+                Some(Symmetry::Rotational),
+                Seed::new(5),
+                budget,
+            )
+            .unwrap();
+        assert_eq!(puzzle.difficulty(), Difficulty::Medium);
+        assert!(puzzle.symmetries().contains(&Symmetry::Rotational));
+    }
+
+    #[test]
+    fn test_generate_with_mask() {
+        let template = Board::empty_with_boxes(2, 3);
+        let mask = template.generate_with_seed(Seed::new(1));
+        let budget = Duration::from_secs(60);
+        let (puzzle, seed) = template
+            .generate_with_mask(&mask, Seed::new(100), budget)
+            .unwrap();
+        assert_eq!(puzzle.count_solutions(2), 1);
+        for (pos, &value) in mask.values.indexed_iter() {
+            assert_eq!(puzzle.values[pos] > 0, value > 0);
+        }
+        let (again, _) = template.generate_with_mask(&mask, seed, budget).unwrap();
+        assert_eq!(again.values, puzzle.values);
+
+        // the mask has enough cells but they cannot fix the whole grid: the numbers of the
+        // first two rows of a box could be swapped
+        let mut mask = Board::empty_with_boxes(2, 2);
+        for j in 0..4 {
+            mask.values[(2, j)] = 1;
+            mask.values[(3, j)] = 1;
+        }
+        let generated =
+            Board::empty_with_boxes(2, 2).generate_with_mask(&mask, Seed::new(1), budget);
+        assert_eq!(generated.err(), Some(MaskError::NoUniquePuzzle));
+
+        // too few cells are refused before searching
+        let mut mask = Board::empty();
+        mask.values[(0, 0)] = 1;
+        let generated = Board::empty().generate_with_mask(&mask, Seed::new(1), budget);
+        assert!(matches!(
+            generated,
+            Err(MaskError::TooFewCells {
+                cells: 1,
+                needed: 17
+            })
+        ));
+    }
+
+    #[test]
+    fn test_min_givens() {
+        assert_eq!(Board::empty().min_givens(), 17);
+        assert_eq!(Board::empty_with_boxes(2, 3).min_givens(), 5);
+        let mut board = Board::empty();
+        board.set_diagonal(true);
+        assert_eq!(board.min_givens(), 8);
+        board.add_cage(Cage::new(3, vec![(0, 0), (0, 1)]));
+        assert_eq!(board.min_givens(), 0);
+        assert_eq!(
+            Board::from_board_dir("variants/quiz-thermo.txt").min_givens(),
+            0
+        );
+
+        let mut board = Board::empty_with_boxes(2, 2);
+        board.values[(0, 1)] = 3;
+        board.values[(3, 3)] = 1;
+        assert_eq!(board.givens_pattern(), ".1../..../..../...1");
+    }
+
+    #[test]
+    fn test_bad_masks() {
+        let budget = Duration::from_secs(1);
+        let mut template = Board::empty();
+        template.values[(4, 4)] = 5;
+        let mask = Board::empty().generate_with_seed(Seed::new(1));
+        let generated = template.generate_with_mask(&Board::empty(), Seed::new(1), budget);
+        assert_eq!(generated.err(), Some(MaskError::NoCells));
+        let generated = template.generate_with_mask(&mask, Seed::new(1), budget);
+        assert_eq!(
+            generated.err(),
+            Some(MaskError::GivenOutsideMask { row: 5, col: 5 })
+        );
+        let small_mask = Board::empty_with_boxes(2, 2).generate_with_seed(Seed::new(1));
+        let generated = Board::empty().generate_with_mask(&small_mask, Seed::new(1), budget);
+        assert_eq!(
+            generated.err(),
+            Some(MaskError::WrongSize {
+                rows: 4,
+                expected: 9
+            })
+        );
+
+        // two 1s in the first row, both inside the mask
+        let mut template = Board::empty_with_boxes(2, 2);
+        template.values[(0, 0)] = 1;
+        template.values[(0, 1)] = 1;
+        let mut mask = Board::empty_with_boxes(2, 2);
+        mask.values.fill(1);
+        let generated = template.generate_with_mask(&mask, Seed::new(1), budget);
+        assert_eq!(generated.err(), Some(MaskError::Unsolvable));
+    }
+
+    #[test]
+    #[should_panic(expected = "no solution")]
+    fn test_generate_unsolvable() {
+        let mut template = Board::empty_with_boxes(2, 2);
+        template.values[(0, 0)] = 1;
+        template.values[(0, 1)] = 1;
+        template.generate();
+    }
+}
@@ -1,6 +1,8 @@
 use ndarray::prelude::*;
+use rand::{seq::SliceRandom, Rng};
+use std::{rc::Rc, sync::Arc};
 
-use crate::Board;
+use crate::{Board, Constraint, Difficulty, MultiGrid};
 
 #[derive(Debug, Clone)]
 struct GuessWrongError {
@@ -11,11 +13,11 @@ struct GuessWrongError {
 #[derive(Debug, Clone)]
 pub struct UnsolvableError;
 
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Debug)]
 enum Guess {
     Prior(u8),
     Hit(u8),
-    Match([bool; 9]),
+    Match(Vec<bool>),
 }
 
 impl Guess {
@@ -30,7 +32,7 @@ impl Guess {
     fn can_be_resolved(&self) -> bool {
         match self {
             Guess::Hit(_) | Guess::Prior(_) => false,
-            Guess::Match(arr) => arr.map(|x| if x { 1 } else { 0 }).iter().sum::<usize>() == 1,
+            Guess::Match(arr) => arr.iter().filter(|&&x| x).count() == 1,
         }
     }
 
@@ -68,6 +70,18 @@ impl Guess {
             Guess::Match(arr) => arr.iter().all(|x| !x),
         }
     }
+
+    /// the numbers the cell can hold, a Hit or a Prior can only hold their own number
+    fn possible_values(&self, size: usize) -> Vec<bool> {
+        match self {
+            Guess::Hit(n) | Guess::Prior(n) => {
+                let mut arr = vec![false; size];
+                arr[*n as usize - 1] = true;
+                arr
+            }
+            Guess::Match(arr) => arr.clone(),
+        }
+    }
 }
 
 #[allow(dead_code)]
@@ -76,33 +90,48 @@ struct BoardSolver {
     board_to_solve: Board,
     solving_board: Board,
     guess_board: Array2<Guess>,
+    /// cells sharing a unit with every cell, computed once and shared by the guesses
+    peers: Rc<Array2<Vec<(usize, usize)>>>,
+    /// the rules of the board, see `Board::constraints`
+    constraints: Rc<Vec<Arc<dyn Constraint>>>,
 }
 
 impl BoardSolver {
     fn from_board(board: Board) -> Self {
-        let multiple_match: [bool; 9] = [true; 9];
+        let size = board.size();
+        let multiple_match = vec![true; size];
         let mut guess_array: Array2<Guess> =
-            Array2::from_elem((9, 9), Guess::Match(multiple_match));
-        for i in 0..9 {
-            for j in 0..9 {
+            Array2::from_elem((size, size), Guess::Match(multiple_match.clone()));
+        for i in 0..size {
+            for j in 0..size {
+                // an even or odd cell starts with the numbers of its parity only
+                let matches = match board.parity_of(i, j) {
+                    Some(parity) => (1..=size as u8).map(|n| parity.allows(n)).collect(),
+                    None => multiple_match.clone(),
+                };
                 match board.values[[i, j]] {
-                    0 => guess_array[[i, j]] = Guess::Match(multiple_match),
-                    el @ 1..=9 => guess_array[[i, j]] = Guess::Prior(el),
-                    _ => panic!("number higher than 9 are not accepted!"),
+                    0 => guess_array[[i, j]] = Guess::Match(matches),
+                    el if el as usize <= size => guess_array[[i, j]] = Guess::Prior(el),
+                    _ => panic!("number higher than {} are not accepted!", size),
                 };
             }
         }
+        let peers = Rc::new(peers_of(&board));
+        let constraints = Rc::new(board.constraints());
         Self {
             solving_board: board.clone(),
             board_to_solve: board,
             guess_board: guess_array,
+            peers,
+            constraints,
         }
     }
 
     /// apply_hits will return the number of hits applied
     fn apply_hits(&mut self) {
-        for i in 0..9 {
-            for j in 0..9 {
+        let size = self.solving_board.size();
+        for i in 0..size {
+            for j in 0..size {
                 if let Guess::Hit(n) = self.guess_board[[i, j]] {
                     self.solving_board.values[[i, j]] = n;
                 }
@@ -112,69 +141,237 @@ impl BoardSolver {
 
     fn can_contain(&self, cell_position: (usize, usize), cell_value: u8) -> bool {
         let values = &self.solving_board.values;
-        let check_row = values.row(cell_position.0).iter().all(|&x| x != cell_value);
-        let check_col = values
-            .column(cell_position.1)
+        self.peers[cell_position]
             .iter()
-            .all(|&x| x != cell_value);
-        let square_row = (cell_position.0 / 3) * 3;
-        let square_col = (cell_position.1 / 3) * 3;
-        let check_square = values
-            .slice(s![square_row..square_row + 3, square_col..square_col + 3])
-            .iter()
-            .all(|&x| x != cell_value);
-        if check_row && check_col && check_square {
-            true
+            .all(|&pos| values[pos] != cell_value)
+    }
+
+    fn exclude_matches(&mut self, row: usize, col: usize) -> Result<i32, UnsolvableError> {
+        let excluded: Vec<usize> = match &self.guess_board[[row, col]] {
+            Guess::Match(arr) => (0..arr.len())
+                .filter(|&i| arr[i] && !self.can_contain((row, col), (i + 1) as u8))
+                .collect(),
+            _ => return Ok(0),
+        };
+        self.exclude_from((row, col), &excluded)
+    }
+
+    /// exclude the matches with the given indexes from the cell, resolving it into an Hit
+    /// when a single match is left
+    fn exclude_from(
+        &mut self,
+        pos: (usize, usize),
+        excluded: &[usize],
+    ) -> Result<i32, UnsolvableError> {
+        let mut guess = self.guess_board[[pos.0, pos.1]].clone();
+        for &i in excluded {
+            guess.exclude(i);
+        }
+        if guess.is_unsolvable() {
+            return Err(UnsolvableError);
+        }
+        if guess.can_be_resolved() {
+            self.guess_board[[pos.0, pos.1]] = guess.resolve();
         } else {
-            false
+            self.guess_board[[pos.0, pos.1]] = guess;
         }
+        Ok(excluded.len() as i32)
     }
 
-    fn exclude_matches(&mut self, row: usize, col: usize) -> Result<i32, UnsolvableError> {
+    /// keep only the `possible` numbers of the cell,
+    /// an Hit or a Prior that is not possible makes the board unsolvable
+    fn restrict_matches(
+        &mut self,
+        pos: (usize, usize),
+        possible: &[bool],
+    ) -> Result<i32, UnsolvableError> {
+        let excluded: Vec<usize> = match &self.guess_board[[pos.0, pos.1]] {
+            Guess::Hit(n) | Guess::Prior(n) if possible[*n as usize - 1] => return Ok(0),
+            Guess::Hit(_) | Guess::Prior(_) => return Err(UnsolvableError),
+            Guess::Match(arr) => (0..arr.len()).filter(|&i| arr[i] && !possible[i]).collect(),
+        };
+        self.exclude_from(pos, &excluded)
+    }
+
+    /// exclude the matches pruned by the constraints, each of them sees the candidates left
+    /// by the previous ones
+    fn exclude_constraint_matches(&mut self) -> Result<i32, UnsolvableError> {
+        let size = self.solving_board.size();
+        let mut candidates = self.guess_board.map(|guess| guess.possible_values(size));
         let mut counter = 0;
-        if let Guess::Match(arr) = &self.guess_board[[row, col]] {
-            let mut guess = self.guess_board[[row, col]];
-            for (i, is_a_match) in arr.to_owned().iter().enumerate() {
-                if *is_a_match && !self.can_contain((row, col), (i + 1) as u8) {
-                    guess.exclude(i);
-                    counter += 1;
+        for constraint in Rc::clone(&self.constraints).iter() {
+            for (pos, possible) in constraint.prune(&candidates) {
+                let excluded = self.restrict_matches(pos, &possible)?;
+                if excluded > 0 {
+                    candidates[pos] = self.guess_board[pos].possible_values(size);
+                    counter += excluded;
                 }
             }
-            if guess.can_be_resolved() {
-                self.guess_board[[row, col]] = guess.resolve();
-            } else {
-                self.guess_board[[row, col]] = guess;
+        }
+        Ok(counter)
+    }
+
+    /// whether the numbers placed so far follow every rule of the board
+    fn is_valid(&self) -> bool {
+        let values = &self.solving_board.values;
+        self.constraints
+            .iter()
+            .all(|constraint| constraint.check(values))
+    }
+
+    /// exclude the matches of every cell and apply the hits found,
+    /// returns the number of matches excluded
+    fn exclude_all_matches(&mut self) -> Result<i32, UnsolvableError> {
+        let mut counter = self.exclude_peer_matches()?;
+        counter += self.exclude_constraint_matches()?;
+        self.apply_hits();
+        Ok(counter)
+    }
+
+    /// exclude the numbers already in the peers of every cell (naked singles) and apply the
+    /// hits found, returns the number of matches excluded
+    fn exclude_peer_matches(&mut self) -> Result<i32, UnsolvableError> {
+        let mut counter = 0;
+        let size = self.solving_board.size();
+        for i in 0..size {
+            for j in 0..size {
+                counter += self.exclude_matches(i, j)?;
             }
-            if guess.is_unsolvable() {
-                return Err(UnsolvableError);
+        }
+        self.apply_hits();
+        Ok(counter)
+    }
+
+    /// exclude the match of `n` (from 0) from the cell, an Hit or a Prior of `n + 1` makes the
+    /// board unsolvable
+    fn exclude_number(&mut self, pos: (usize, usize), n: usize) -> Result<i32, UnsolvableError> {
+        let size = self.solving_board.size();
+        let possible: Vec<bool> = (0..size).map(|m| m != n).collect();
+        self.restrict_matches(pos, &possible)
+    }
+
+    /// when the matches of a number in a unit all lay in the cells it shares with another
+    /// unit, exclude the number from the other cells of the second unit (locked candidates)
+    fn exclude_locked_matches(&mut self) -> Result<i32, UnsolvableError> {
+        let size = self.solving_board.size();
+        let units: Vec<Vec<(usize, usize)>> = self
+            .solving_board
+            .units()
+            .into_iter()
+            .filter(|unit| unit.len() == size)
+            .collect();
+        let mut counter = 0;
+        for unit in units.iter() {
+            for n in 0..size {
+                let holders: Vec<(usize, usize)> = unit
+                    .iter()
+                    .copied()
+                    .filter(|&pos| self.guess_board[pos].possible_values(size)[n])
+                    .collect();
+                if holders.len() < 2 {
+                    continue;
+                }
+                for other in units.iter() {
+                    if other == unit || !holders.iter().all(|pos| other.contains(pos)) {
+                        continue;
+                    }
+                    for &pos in other.iter().filter(|pos| !unit.contains(pos)) {
+                        counter += self.exclude_number(pos, n)?;
+                    }
+                }
             }
         }
         Ok(counter)
     }
 
-    fn simulate_path(&mut self) -> Result<Board, GuessWrongError> {
-        let mut new_solver = self.clone();
+    /// when two cells of a unit can only hold the same two numbers, exclude them from the
+    /// other cells of the unit (naked pairs)
+    fn exclude_pair_matches(&mut self) -> Result<i32, UnsolvableError> {
+        let size = self.solving_board.size();
+        let mut counter = 0;
+        for unit in self.solving_board.units() {
+            let pairs: Vec<((usize, usize), Vec<bool>)> = unit
+                .iter()
+                .filter_map(|&pos| match &self.guess_board[pos] {
+                    Guess::Match(arr) if arr.iter().filter(|&&x| x).count() == 2 => {
+                        Some((pos, arr.clone()))
+                    }
+                    _ => None,
+                })
+                .collect();
+            for (k, (first, numbers)) in pairs.iter().enumerate() {
+                for (second, _) in pairs[k + 1..].iter().filter(|(_, arr)| arr == numbers) {
+                    for &pos in unit.iter().filter(|&pos| pos != first && pos != second) {
+                        for n in (0..size).filter(|&n| numbers[n]) {
+                            counter += self.exclude_number(pos, n)?;
+                        }
+                    }
+                }
+            }
+        }
+        Ok(counter)
+    }
 
-        // look for the first mathc with minimun guesses
-        let mut record: (usize, usize) = (9, 0);
-        for i in 0..81 {
-            if let Guess::Match(arr) = &mut new_solver.guess_board[[i / 9, i % 9]] {
+    /// apply the techniques of `difficulty` until they exclude no more matches
+    fn reduce(&mut self, difficulty: Difficulty) -> Result<(), UnsolvableError> {
+        loop {
+            let mut counter = match difficulty {
+                Difficulty::Easy => self.exclude_peer_matches()?,
+                _ => self.exclude_all_matches()?,
+            };
+            if counter == 0 && difficulty >= Difficulty::Hard {
+                counter += self.exclude_locked_matches()?;
+                counter += self.exclude_pair_matches()?;
+                self.apply_hits();
+            }
+            if counter == 0 {
+                return Ok(());
+            }
+        }
+    }
+
+    /// the lowest difficulty whose techniques solve the board
+    fn difficulty(&self) -> Difficulty {
+        for difficulty in [Difficulty::Easy, Difficulty::Medium, Difficulty::Hard] {
+            let mut solver = self.clone();
+            if solver.reduce(difficulty).is_ok()
+                && solver.solving_board.is_complete()
+                && solver.is_valid()
+            {
+                return difficulty;
+            }
+        }
+        Difficulty::Expert
+    }
+
+    /// position of the first match with minimum guesses
+    fn most_constrained_cell(&self) -> (usize, usize) {
+        let size = self.solving_board.size();
+        let mut record: (usize, usize) = (size + 1, 0);
+        for i in 0..size * size {
+            if let Guess::Match(arr) = &self.guess_board[[i / size, i % size]] {
                 let possible_matches = arr.iter().filter(|&x| *x).count();
                 if record.0 > possible_matches {
                     record = (possible_matches, i);
                 }
             }
         }
+        (record.1 / size, record.1 % size)
+    }
+
+    fn simulate_path(&mut self) -> Result<Board, GuessWrongError> {
+        let mut new_solver = self.clone();
+
         // apply a the first hit on the most likely match
-        let first_match_ix = new_solver.guess_board[[record.1 / 9, record.1 % 9]].first_match_ix();
-        new_solver.guess_board[[record.1 / 9, record.1 % 9]] =
-            Guess::Hit((first_match_ix + 1) as u8);
+        let pos = new_solver.most_constrained_cell();
+        let first_match_ix = new_solver.guess_board[[pos.0, pos.1]].first_match_ix();
+        new_solver.guess_board[[pos.0, pos.1]] = Guess::Hit((first_match_ix + 1) as u8);
         new_solver.apply_hits();
 
         match new_solver.solve() {
             Ok(solved_board) => Ok(solved_board),
             Err(_) => Err(GuessWrongError {
-                pos: (record.1 / 9, record.1 % 9),
+                pos,
                 wrong_guess_ix: first_match_ix,
             }),
         }
@@ -184,19 +381,13 @@ impl BoardSolver {
         // flags
         let mut can_be_reduced: bool = true;
 
-        if !self.board_to_solve.check_complete() {
+        if !self.is_valid() {
             return Err(UnsolvableError);
         }
 
         while !self.solving_board.is_complete() {
-            let mut counter = 0;
             if can_be_reduced {
-                for i in 0..9 {
-                    for j in 0..9 {
-                        counter += self.exclude_matches(i, j)?;
-                    }
-                }
-                self.apply_hits();
+                let counter = self.exclude_all_matches()?;
                 if counter == 0 {
                     can_be_reduced = false;
                 }
@@ -214,23 +405,260 @@ impl BoardSolver {
             }
         }
 
-        if self.solving_board.check_complete() {
+        if self.is_valid() {
             Ok(self.solving_board)
         } else {
             Err(UnsolvableError)
         }
     }
+
+    /// collect the solutions trying every match of the most constrained cell,
+    /// it stops as soon as `limit` solutions are found
+    fn search(mut self, limit: usize, solutions: &mut Vec<Board>) {
+        loop {
+            match self.exclude_all_matches() {
+                Ok(0) => break,
+                Ok(_) => (),
+                Err(_) => return,
+            }
+        }
+        if !self.is_valid() {
+            return;
+        }
+        if self.solving_board.is_complete() {
+            return solutions.push(self.solving_board);
+        }
+
+        let pos = self.most_constrained_cell();
+        let matches = match &self.guess_board[[pos.0, pos.1]] {
+            Guess::Match(arr) => arr.clone(),
+            _ => panic!("this should not occur"),
+        };
+        for (ix, _) in matches.iter().enumerate().filter(|(_, &x)| x) {
+            let mut new_solver = self.clone();
+            new_solver.guess_board[[pos.0, pos.1]] = Guess::Hit((ix + 1) as u8);
+            new_solver.apply_hits();
+            new_solver.search(limit, solutions);
+            if solutions.len() >= limit {
+                return;
+            }
+        }
+    }
+
+    /// a solution found trying the matches of the most constrained cell in random order,
+    /// `None` when there is no solution
+    fn random_solution<R: Rng>(mut self, rng: &mut R) -> Option<Board> {
+        loop {
+            match self.exclude_all_matches() {
+                Ok(0) => break,
+                Ok(_) => (),
+                Err(_) => return None,
+            }
+        }
+        if !self.is_valid() {
+            return None;
+        }
+        if self.solving_board.is_complete() {
+            return Some(self.solving_board);
+        }
+
+        let pos = self.most_constrained_cell();
+        let mut matches: Vec<usize> = match &self.guess_board[[pos.0, pos.1]] {
+            Guess::Match(arr) => (0..arr.len()).filter(|&ix| arr[ix]).collect(),
+            _ => panic!("this should not occur"),
+        };
+        matches.shuffle(rng);
+        matches.into_iter().find_map(|ix| {
+            let mut new_solver = self.clone();
+            new_solver.guess_board[[pos.0, pos.1]] = Guess::Hit((ix + 1) as u8);
+            new_solver.apply_hits();
+            new_solver.random_solution(rng)
+        })
+    }
+}
+
+/// a cell of the canvas seen from a grid: the index of the grid and the position in it
+type View = (usize, (usize, usize));
+
+/// the solvers of the grids of a multi-grid puzzle, each one applies the rules of its grid
+/// and the cells shared by more grids are narrowed to the matches all their views allow
+#[derive(Clone)]
+struct MultiGridSolver {
+    solvers: Vec<BoardSolver>,
+    /// the views of each cell of the canvas covered by more grids
+    shared: Rc<Vec<Vec<View>>>,
+}
+
+impl MultiGridSolver {
+    fn from_multi_grid(multi_grid: &MultiGrid) -> Self {
+        let solvers = multi_grid
+            .grids()
+            .iter()
+            .map(|grid| BoardSolver::from_board(grid.clone()))
+            .collect();
+        let (rows, cols) = multi_grid.canvas_shape();
+        let shared = (0..rows)
+            .flat_map(|i| (0..cols).map(move |j| (i, j)))
+            .map(|(i, j)| multi_grid.views_of(i, j))
+            .filter(|views| views.len() > 1)
+            .collect();
+        Self {
+            solvers,
+            shared: Rc::new(shared),
+        }
+    }
+
+    /// keep in every view of a shared cell only the matches of the other views too and apply
+    /// the hits found, returns the number of matches excluded
+    fn exclude_shared_matches(&mut self) -> Result<i32, UnsolvableError> {
+        let mut counter = 0;
+        for views in Rc::clone(&self.shared).iter() {
+            let mut possible: Option<Vec<bool>> = None;
+            for &(g, pos) in views.iter() {
+                let solver = &self.solvers[g];
+                let matches = solver.guess_board[pos].possible_values(solver.solving_board.size());
+                possible = Some(match possible {
+                    Some(possible) => possible.iter().zip(matches).map(|(&a, b)| a && b).collect(),
+                    None => matches,
+                });
+            }
+            let possible = possible.unwrap();
+            for &(g, pos) in views.iter() {
+                counter += self.solvers[g].restrict_matches(pos, &possible)?;
+            }
+        }
+        for solver in self.solvers.iter_mut() {
+            solver.apply_hits();
+        }
+        Ok(counter)
+    }
+
+    /// exclude the matches of every grid and share them until no more are excluded
+    fn reduce(&mut self) -> Result<(), UnsolvableError> {
+        loop {
+            let mut counter = self.exclude_shared_matches()?;
+            for solver in self.solvers.iter_mut() {
+                counter += solver.exclude_all_matches()?;
+            }
+            if counter == 0 {
+                return Ok(());
+            }
+        }
+    }
+
+    /// collect the solved grids reachable trying every match of the most constrained cell,
+    /// it stops as soon as `limit` solutions are found
+    fn search(mut self, limit: usize, solutions: &mut Vec<Vec<Board>>) {
+        if self.reduce().is_err() || !self.solvers.iter().all(|solver| solver.is_valid()) {
+            return;
+        }
+        // the cell with the fewest matches among the open cells of every grid
+        let open = self
+            .solvers
+            .iter()
+            .enumerate()
+            .filter(|(_, solver)| !solver.solving_board.is_complete())
+            .map(|(g, solver)| {
+                let pos = solver.most_constrained_cell();
+                let size = solver.solving_board.size();
+                (g, pos, solver.guess_board[pos].possible_values(size))
+            })
+            .min_by_key(|(_, _, matches)| matches.iter().filter(|&&x| x).count());
+        let (g, pos, matches) = match open {
+            Some(open) => open,
+            None => {
+                let grids = self.solvers.into_iter().map(|s| s.solving_board).collect();
+                return solutions.push(grids);
+            }
+        };
+        for (ix, _) in matches.iter().enumerate().filter(|(_, &x)| x) {
+            let mut next = self.clone();
+            next.solvers[g].guess_board[pos] = Guess::Hit((ix + 1) as u8);
+            next.solvers[g].apply_hits();
+            next.search(limit, solutions);
+            if solutions.len() >= limit {
+                return;
+            }
+        }
+    }
+}
+
+/// the cells that cannot hold the same number of each cell of the board: the ones sharing
+/// at least a unit with it, and the ones a chess move away for the anti-knight and anti-king
+/// rules
+fn peers_of(board: &Board) -> Array2<Vec<(usize, usize)>> {
+    let size = board.size();
+    let mut peers = Array2::from_elem((size, size), Vec::new());
+    for unit in board.units() {
+        for &cell in unit.iter() {
+            for &other in unit.iter() {
+                if other != cell && !peers[cell].contains(&other) {
+                    peers[cell].push(other);
+                }
+            }
+        }
+    }
+    for ((i, j), cell_peers) in peers.indexed_iter_mut() {
+        for other in board.chess_peers(i, j) {
+            if !cell_peers.contains(&other) {
+                cell_peers.push(other);
+            }
+        }
+    }
+    peers
 }
 
 impl Board {
     pub fn solve(&self) -> Result<Board, UnsolvableError> {
         BoardSolver::from_board(self.clone()).solve()
     }
+
+    /// how hard the board is to solve without guessing, the board is expected to have a
+    /// unique solution
+    pub fn difficulty(&self) -> Difficulty {
+        BoardSolver::from_board(self.clone()).difficulty()
+    }
+
+    /// a random solution of the board, `None` when it has none
+    pub(crate) fn random_solution<R: Rng>(&self, rng: &mut R) -> Option<Board> {
+        BoardSolver::from_board(self.clone()).random_solution(rng)
+    }
+
+    /// number of solutions of the board, counting stops at `limit`
+    /// (e.g. a limit of 2 is enough to know whether the solution is unique)
+    pub fn count_solutions(&self, limit: usize) -> usize {
+        self.solutions(limit).len()
+    }
+
+    /// the solutions of the board, at most `limit` of them
+    pub(crate) fn solutions(&self, limit: usize) -> Vec<Board> {
+        let mut solutions = Vec::new();
+        BoardSolver::from_board(self.clone()).search(limit, &mut solutions);
+        solutions
+    }
+}
+
+impl MultiGrid {
+    pub fn solve(&self) -> Result<MultiGrid, UnsolvableError> {
+        let mut solutions = Vec::new();
+        MultiGridSolver::from_multi_grid(self).search(1, &mut solutions);
+        let mut solved = self.clone();
+        solved.grids = solutions.pop().ok_or(UnsolvableError)?;
+        Ok(solved)
+    }
+
+    /// number of solutions of the puzzle, counting stops at `limit`
+    pub fn count_solutions(&self, limit: usize) -> usize {
+        let mut solutions = Vec::new();
+        MultiGridSolver::from_multi_grid(self).search(limit, &mut solutions);
+        solutions.len()
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::{Arrow, Cage, Direction, LittleKiller, Thermo, Xv};
 
     mod complete {
         use super::*;
@@ -287,6 +715,377 @@ mod tests {
         test_board!(test_hard_01, 11);
     }
 
+    mod sizes {
+        use super::*;
+
+        macro_rules! test_sized_board {
+            ($test_name: ident, $size: expr) => {
+                #[test]
+                fn $test_name() {
+                    let board_to_solve = Board::from_board_dir(
+                        format!("sizes/quiz-{0:0>2}x{0:0>2}.txt", $size).as_str(),
+                    );
+                    let expected_board = Board::from_board_dir(
+                        format!("sizes/solution-{0:0>2}x{0:0>2}.txt", $size).as_str(),
+                    );
+                    let solved_board = board_to_solve.solve().unwrap();
+                    assert!(solved_board.check_complete());
+
+                    println!("solved board:");
+                    solved_board.print_simple();
+
+                    assert_eq!(solved_board.values, expected_board.values);
+                }
+            };
+        }
+
+        test_sized_board!(test_4x4, 4);
+        test_sized_board!(test_6x6, 6);
+        test_sized_board!(test_8x8, 8);
+        test_sized_board!(test_12x12, 12);
+        test_sized_board!(test_16x16, 16);
+        test_sized_board!(test_25x25, 25);
+
+        #[test]
+        fn test_tall_boxes() {
+            let board_to_solve = Board::from_board_dir("sizes/quiz-06x06-3x2.txt");
+            let expected_board = Board::from_board_dir("sizes/solution-06x06-3x2.txt");
+            let solved_board = board_to_solve.solve().unwrap();
+            assert_eq!(solved_board.values, expected_board.values);
+        }
+
+        #[test]
+        fn test_empty_4x4() {
+            let solved_board = Board::empty_with_boxes(2, 2).solve().unwrap();
+            assert!(solved_board.is_complete());
+            assert!(solved_board.check_complete());
+        }
+    }
+
+    mod variants {
+        use super::*;
+
+        macro_rules! test_variant {
+            ($test_name: ident, $name: expr) => {
+                #[test]
+                fn $test_name() {
+                    let board_to_solve =
+                        Board::from_board_dir(concat!("variants/quiz-", $name, ".txt"));
+                    let expected_board =
+                        Board::from_board_dir(concat!("variants/solution-", $name, ".txt"));
+                    let solved_board = board_to_solve.solve().unwrap();
+                    assert!(solved_board.check_complete());
+                    assert_eq!(solved_board.values, expected_board.values);
+                    assert_eq!(board_to_solve.count_solutions(2), 1);
+                }
+            };
+        }
+
+        test_variant!(test_diagonal, "diagonal");
+        test_variant!(test_jigsaw, "jigsaw");
+        // no givens at all, only the cages
+        test_variant!(test_killer, "killer");
+        test_variant!(test_hyper, "hyper");
+        test_variant!(test_anti_knight, "anti-knight");
+        test_variant!(test_anti_king, "anti-king");
+        test_variant!(test_non_consecutive, "non-consecutive");
+        // a single given, the dots and the negative rule do the rest
+        test_variant!(test_kropki, "kropki");
+        test_variant!(test_xv, "xv");
+        test_variant!(test_little_killer, "little-killer");
+        test_variant!(test_latin_square, "latin-square");
+        test_variant!(test_thermo, "thermo");
+        test_variant!(test_sandwich, "sandwich");
+        test_variant!(test_arrow, "arrow");
+        test_variant!(test_even_odd, "even-odd");
+        // three givens, the signs inside the boxes do the rest
+        test_variant!(test_greater_than, "greater-than");
+
+        #[test]
+        fn test_without_the_rule() {
+            // the solution of each puzzle is not unique without its own rule
+            type RemoveRule = fn(&mut Board);
+            let rules: [(&str, RemoveRule); 5] = [
+                ("diagonal", |board| board.set_diagonal(false)),
+                ("hyper", |board| board.set_hyper(false)),
+                ("anti-knight", |board| board.set_anti_knight(false)),
+                ("anti-king", |board| board.set_anti_king(false)),
+                ("non-consecutive", |board| board.set_non_consecutive(false)),
+            ];
+            for (name, remove_rule) in rules {
+                let mut board =
+                    Board::from_board_dir(format!("variants/quiz-{}.txt", name).as_str());
+                remove_rule(&mut board);
+                assert_eq!(board.count_solutions(2), 2, "{}", name);
+            }
+        }
+
+        #[test]
+        fn test_parity_matches() {
+            let board = Board::from_board_dir("variants/quiz-even-odd.txt");
+            let solver = BoardSolver::from_board(board);
+            let possible = solver.guess_board[[0, 7]].possible_values(9);
+            assert_eq!(
+                possible,
+                [false, true, false, true, false, true, false, true, false]
+            );
+        }
+
+        #[test]
+        fn test_exclude_cage_matches() {
+            let mut board = Board::empty();
+            board.add_cage(Cage::new(3, vec![(0, 0), (0, 1)]));
+            board.add_cage(Cage::new(24, vec![(1, 0), (1, 1), (1, 2)]));
+            let mut solver = BoardSolver::from_board(board);
+            solver.exclude_constraint_matches().unwrap();
+            let possible = solver.guess_board[[0, 0]].possible_values(9);
+            assert_eq!(
+                possible,
+                [true, true, false, false, false, false, false, false, false]
+            );
+            let possible = solver.guess_board[[1, 2]].possible_values(9);
+            assert_eq!(
+                possible,
+                [false, false, false, false, false, false, true, true, true]
+            );
+        }
+
+        #[test]
+        fn test_peers() {
+            let mut board = Board::empty();
+            assert_eq!(peers_of(&board)[(0, 0)].len(), 20);
+            board.set_hyper(true);
+            let peers = peers_of(&board);
+            assert_eq!(peers[(0, 0)].len(), 20);
+            // (1, 1) also sees the cells of its window outside its row, column and box
+            assert_eq!(peers[(1, 1)].len(), 23);
+        }
+
+        #[test]
+        fn test_exclude_sum_matches() {
+            // an X leaves 5 out (5 + 5 repeats a number) and a little killer of 3 along two cells only allows 1 + 2
+            let mut board = Board::empty();
+            board.add_xv(((0, 0), (0, 1)), Xv::X);
+            board.add_little_killer(LittleKiller::new(3, (7, 0), Direction::DownRight));
+            let mut solver = BoardSolver::from_board(board);
+            solver.exclude_constraint_matches().unwrap();
+            let possible = solver.guess_board[[0, 0]].possible_values(9);
+            assert_eq!(possible.iter().filter(|&&x| x).count(), 8);
+            assert!(!possible[4]);
+            let possible = solver.guess_board[[8, 1]].possible_values(9);
+            assert_eq!(
+                possible,
+                [true, true, false, false, false, false, false, false, false]
+            );
+        }
+
+        /// the numbers of the first row increase from left to right, without any pruning
+        struct Increasing;
+
+        impl Constraint for Increasing {
+            fn check(&self, values: &Array2<u8>) -> bool {
+                let row: Vec<u8> = values.row(0).iter().copied().filter(|&n| n > 0).collect();
+                row.windows(2).all(|pair| pair[0] < pair[1])
+            }
+        }
+
+        /// a cell holding a fixed number
+        struct Fixed((usize, usize), u8);
+
+        impl Constraint for Fixed {
+            fn check(&self, values: &Array2<u8>) -> bool {
+                values[self.0] == 0 || values[self.0] == self.1
+            }
+
+            fn prune(&self, candidates: &Array2<Vec<bool>>) -> Vec<((usize, usize), Vec<bool>)> {
+                let size = candidates[self.0].len();
+                let possible = (1..=size as u8).map(|n| n == self.1).collect();
+                vec![(self.0, possible)]
+            }
+        }
+
+        #[test]
+        fn test_custom_constraints() {
+            // 288 grids of 4x4, one in 24 has the first row in increasing order
+            let mut board = Board::empty_with_boxes(2, 2);
+            board.add_constraint(Increasing);
+            assert_eq!(board.count_solutions(100), 12);
+            let solved_board = board.solve().unwrap();
+            assert_eq!(solved_board.values.row(0).to_vec(), [1, 2, 3, 4]);
+
+            // the fixed number is applied before any guess
+            let mut board = Board::empty();
+            board.add_constraint(Fixed((4, 4), 7));
+            let mut solver = BoardSolver::from_board(board.clone());
+            solver.exclude_all_matches().unwrap();
+            assert_eq!(solver.solving_board.values[(4, 4)], 7);
+            assert_eq!(board.solve().unwrap().values[(4, 4)], 7);
+        }
+
+        #[test]
+        fn test_difficulty() {
+            let boards = [
+                ("complete/quiz-00.txt", Difficulty::Easy),
+                ("variants/quiz-jigsaw.txt", Difficulty::Medium),
+                ("variants/quiz-hyper.txt", Difficulty::Hard),
+                ("complete/quiz-10.txt", Difficulty::Expert),
+            ];
+            for (path, difficulty) in boards {
+                assert_eq!(
+                    Board::from_board_dir(path).difficulty(),
+                    difficulty,
+                    "{}",
+                    path
+                );
+            }
+
+            // two cells left by a locked number: the 1 of the first box is in its first row
+            let mut board = Board::empty_with_boxes(2, 2);
+            board.values[(1, 2)] = 1;
+            let mut solver = BoardSolver::from_board(board);
+            solver.exclude_all_matches().unwrap();
+            solver.exclude_locked_matches().unwrap();
+            assert!(!solver.guess_board[[0, 3]].possible_values(4)[0]);
+        }
+
+        #[test]
+        fn test_exclude_sandwich_matches() {
+            let mut board = Board::empty();
+            board.set_row_sandwich(0, Some(35));
+            let mut solver = BoardSolver::from_board(board);
+            solver.exclude_constraint_matches().unwrap();
+            let possible = solver.guess_board[[0, 0]].possible_values(9);
+            assert_eq!(
+                possible,
+                [true, false, false, false, false, false, false, false, true]
+            );
+            let possible = solver.guess_board[[0, 4]].possible_values(9);
+            assert!(!possible[0] && !possible[8]);
+        }
+
+        #[test]
+        fn test_exclude_arrow_matches() {
+            let mut board = Board::empty();
+            board.add_arrow(Arrow::new((0, 0), vec![(1, 0), (2, 0)]));
+            board.values[(1, 0)] = 5;
+            let mut solver = BoardSolver::from_board(board);
+            solver.exclude_constraint_matches().unwrap();
+            // 5 and at least 1 make 6 or more in the circle, which leaves at most 4
+            let possible = solver.guess_board[[0, 0]].possible_values(9);
+            assert_eq!(possible.iter().position(|&x| x), Some(5));
+            let possible = solver.guess_board[[2, 0]].possible_values(9);
+            assert_eq!(possible.iter().rposition(|&x| x), Some(3));
+        }
+
+        #[test]
+        fn test_exclude_thermo_matches() {
+            let mut board = Board::empty();
+            board.add_thermo(Thermo::new(vec![(0, 0), (1, 1), (2, 2), (3, 3)]));
+            board.values[(2, 2)] = 4;
+            let mut solver = BoardSolver::from_board(board);
+            solver.exclude_constraint_matches().unwrap();
+            // two cells below the 4 leave 1 and 2 to the bulb, the tip is at least 5
+            let possible = solver.guess_board[[0, 0]].possible_values(9);
+            assert_eq!(
+                possible,
+                [true, true, false, false, false, false, false, false, false]
+            );
+            let possible = solver.guess_board[[3, 3]].possible_values(9);
+            assert_eq!(possible.iter().position(|&x| x), Some(4));
+        }
+
+        #[test]
+        fn test_inequality_chain() {
+            // 1 < 2 < 3 < 4 along the first row of a 4x4 board
+            let mut board = Board::empty_with_boxes(2, 2);
+            for j in 0..3 {
+                board.add_inequality(((0, j), (0, j + 1)), std::cmp::Ordering::Less);
+            }
+            let mut solver = BoardSolver::from_board(board);
+            while solver.exclude_all_matches().unwrap() > 0 {}
+            assert_eq!(
+                solver.solving_board.values.row(0).to_vec(),
+                vec![1, 2, 3, 4]
+            );
+        }
+
+        #[test]
+        fn test_exclude_relation_matches() {
+            let mut board = Board::empty();
+            board.set_non_consecutive(true);
+            board.values[(4, 4)] = 5;
+            let mut solver = BoardSolver::from_board(board);
+            solver.exclude_constraint_matches().unwrap();
+            let possible = solver.guess_board[[3, 4]].possible_values(9);
+            assert_eq!(
+                possible,
+                [true, true, true, false, true, false, true, true, true]
+            );
+            // only the neighbours are affected
+            let possible = solver.guess_board[[3, 3]].possible_values(9);
+            assert!(possible.iter().all(|&x| x));
+        }
+
+        #[test]
+        fn test_count_chess_moves_4x4() {
+            let mut board = Board::empty_with_boxes(2, 2);
+            board.set_anti_knight(true);
+            assert_eq!(board.count_solutions(usize::MAX), 24);
+
+            // the centre cells see each other and the whole box of every corner
+            let mut board = Board::empty_with_boxes(2, 2);
+            board.set_anti_king(true);
+            assert_eq!(board.count_solutions(usize::MAX), 0);
+        }
+
+        #[test]
+        fn test_count_latin_square_4x4() {
+            // there are 576 Latin squares of order 4, and 288 of them are sudokus
+            assert_eq!(Board::empty_latin_square(4).count_solutions(1000), 576);
+        }
+
+        #[test]
+        fn test_count_diagonal_4x4() {
+            let mut board = Board::empty_with_boxes(2, 2);
+            board.set_diagonal(true);
+            assert_eq!(board.count_solutions(usize::MAX), 48);
+        }
+    }
+
+    #[test]
+    fn test_count_solutions() {
+        assert_eq!(
+            Board::empty_with_boxes(2, 2).count_solutions(usize::MAX),
+            288
+        );
+        assert_eq!(Board::empty().count_solutions(10), 10);
+        for file_num in [0, 10, 11] {
+            let board =
+                Board::from_board_dir(format!("complete/quiz-{:0>2}.txt", file_num).as_str());
+            assert_eq!(board.count_solutions(2), 1);
+        }
+        let invalid_board = Board::from_board_dir("tests/two_ones_in_a_row.txt");
+        assert_eq!(invalid_board.count_solutions(2), 0);
+    }
+
+    #[test]
+    fn test_multi_grid_reduce() {
+        let mut twodoku = MultiGrid::with_layout(crate::Layout::Twodoku, 4);
+        // a thermometer along the first row of a grid fixes the row without guesses
+        twodoku.grids[0].add_thermo(Thermo::new((0..4).map(|j| (0, j)).collect()));
+        // a cage on the shared box of the second grid restricts the first grid too
+        twodoku.grids[1].add_cage(Cage::new(3, vec![(0, 0), (0, 1)]));
+        let mut solver = MultiGridSolver::from_multi_grid(&twodoku);
+        solver.reduce().unwrap();
+        let first = &solver.solvers[0];
+        assert_eq!(first.solving_board.values.row(0).to_vec(), [1, 2, 3, 4]);
+        assert_eq!(
+            first.guess_board[(2, 2)].possible_values(4),
+            [true, true, false, false]
+        );
+        assert_eq!(twodoku.count_solutions(2), 2);
+    }
+
     fn test_exlcude_matches_from_file(file_path: &str) {
         let board = Board::from_board_dir(file_path);
         board.print_simple();
@@ -1,42 +1,389 @@
+mod alphabet;
+mod arrow;
+mod cage;
 mod cli;
+mod constraint;
+mod difficulty;
+mod generator;
 mod guess;
+mod little_killer;
+mod multi_grid;
+mod parity;
+mod puzzle_file;
+mod relation;
+mod sandwich;
+mod sum;
+mod symmetry;
+mod thermo;
 mod utils;
 
 use ndarray::prelude::*;
-use std::{fs::File, io::Read};
+use std::{cmp::Ordering, fs::File, io::Read, path::Path, sync::Arc};
+
+use constraint::ChessMoves;
+use relation::{Link, Relation};
+use sandwich::Sandwich;
+use sum::CellSum;
+
+pub use alphabet::Alphabet;
+pub use arrow::Arrow;
+pub use cage::Cage;
+pub use constraint::{Constraint, Unit};
+pub use difficulty::Difficulty;
+pub use generator::{MaskError, Seed};
+pub use little_killer::{Direction, LittleKiller};
+pub use multi_grid::{Layout, MultiGrid};
+pub use parity::Parity;
+pub use relation::{Dot, Edge, Xv};
+pub use symmetry::Symmetry;
+pub use thermo::Thermo;
 
 #[derive(Clone)]
 pub struct Board {
     values: Array2<u8>,
+    /// region of every cell, each region must contain every number once
+    /// (the boxes of a classic sudoku, or irregular shapes in a jigsaw sudoku)
+    regions: Array2<usize>,
+    alphabet: Alphabet,
+    /// only rows and columns cannot repeat a number, the regions are ignored (Latin square)
+    latin_square: bool,
+    /// the two main diagonals must contain every number once (X-Sudoku)
+    diagonal: bool,
+    /// the four windows between the boxes must contain every number once (Hyper Sudoku)
+    hyper: bool,
+    /// groups of cells that must contain every number once besides rows, columns and regions
+    extra_regions: Vec<Vec<(usize, usize)>>,
+    /// cells a chess knight's move apart cannot hold the same number
+    anti_knight: bool,
+    /// cells a chess king's move apart cannot hold the same number
+    anti_king: bool,
+    /// orthogonally adjacent cells cannot hold consecutive numbers
+    non_consecutive: bool,
+    /// dots of a Kropki sudoku, at most one on each edge
+    dots: Vec<(Edge, Dot)>,
+    /// cells without a dot between them are neither consecutive nor one the double of the
+    /// other (negative Kropki sudoku)
+    negative_kropki: bool,
+    /// markers of a XV sudoku, at most one on each edge
+    xvs: Vec<(Edge, Xv)>,
+    /// cells without a marker between them add up to neither 10 nor 5 (negative XV sudoku)
+    negative_xv: bool,
+    /// inequality signs of a greater-than sudoku, comparing the first cell of the edge with
+    /// the second one
+    inequalities: Vec<(Edge, Ordering)>,
+    /// thermometers, they can share cells (e.g. two thermometers with the same bulb)
+    thermos: Vec<Thermo>,
+    /// sandwich clue of every row, the sum of the numbers between the lowest and the highest
+    /// one (the 1 and the 9 of a 9x9 board)
+    row_sandwiches: Vec<Option<u32>>,
+    /// sandwich clue of every column
+    column_sandwiches: Vec<Option<u32>>,
+    /// arrows, the number in the circle is the sum of the numbers along the arrow
+    arrows: Vec<Arrow>,
+    /// parity of the cells of an even/odd sudoku, `None` for the cells without restrictions
+    parities: Array2<Option<Parity>>,
+    /// cages of a killer sudoku, they never overlap
+    cages: Vec<Cage>,
+    /// little killer clues, the sums of the numbers along diagonals
+    little_killers: Vec<LittleKiller>,
+    /// rules added besides the ones of the variants, see `add_constraint`
+    custom_constraints: Vec<Arc<dyn Constraint>>,
 }
 
 impl Board {
     pub fn empty() -> Self {
-        let board = Array2::from_elem((9, 9), 0_u8);
-        Board { values: board }
+        Self::empty_with_boxes(3, 3)
+    }
+
+    /// an empty board made of boxes of `box_rows` x `box_cols` cells, the board has
+    /// `box_rows * box_cols` rows and columns (e.g. 2x3 boxes for a 6x6 board)
+    pub fn empty_with_boxes(box_rows: usize, box_cols: usize) -> Self {
+        let size = box_rows * box_cols;
+        Self::new_with_boxes(Array2::from_elem((size, size), 0_u8), box_rows, box_cols)
     }
 
+    /// the shape of the boxes is guessed from the size of the board, see `box_shape_of`
     pub fn new(values: Array2<u8>) -> Self {
-        Board { values }
+        let (box_rows, box_cols) = box_shape_of(values.nrows());
+        Self::new_with_boxes(values, box_rows, box_cols)
+    }
+
+    pub fn new_with_boxes(values: Array2<u8>, box_rows: usize, box_cols: usize) -> Self {
+        assert_eq!(
+            box_rows * box_cols,
+            values.nrows(),
+            "{}x{} boxes do not fit a board with {} rows",
+            box_rows,
+            box_cols,
+            values.nrows()
+        );
+        let regions = box_regions(values.nrows(), box_rows, box_cols);
+        Self::new_with_regions(values, regions)
+    }
+
+    /// an empty Latin square with `size` rows, any size is allowed
+    pub fn empty_latin_square(size: usize) -> Self {
+        Self::new_latin_square(Array2::from_elem((size, size), 0_u8))
+    }
+
+    /// a Latin square: only rows and columns cannot repeat a number
+    pub fn new_latin_square(values: Array2<u8>) -> Self {
+        let size = values.nrows();
+        let mut board = Self::new_with_boxes(values, 1, size);
+        board.latin_square = true;
+        board
+    }
+
+    /// a board whose regions are given cell by cell, regions are numbered from 0 and
+    /// there must be as many of them as rows, each one made of as many connected cells
+    pub fn new_with_regions(values: Array2<u8>, regions: Array2<usize>) -> Self {
+        assert_eq!(values.nrows(), values.ncols(), "the board must be square");
+        assert_eq!(
+            values.dim(),
+            regions.dim(),
+            "the regions do not have the same shape of the board"
+        );
+        check_regions(&regions);
+        let size = values.nrows();
+        Board {
+            values,
+            regions,
+            alphabet: Alphabet::default(),
+            latin_square: false,
+            diagonal: false,
+            hyper: false,
+            extra_regions: Vec::new(),
+            anti_knight: false,
+            anti_king: false,
+            non_consecutive: false,
+            dots: Vec::new(),
+            negative_kropki: false,
+            xvs: Vec::new(),
+            negative_xv: false,
+            inequalities: Vec::new(),
+            thermos: Vec::new(),
+            row_sandwiches: vec![None; size],
+            column_sandwiches: vec![None; size],
+            arrows: Vec::new(),
+            parities: Array2::from_elem((size, size), None),
+            cages: Vec::new(),
+            little_killers: Vec::new(),
+            custom_constraints: Vec::new(),
+        }
     }
 
+    /// the board is read one line per row and one character per cell, numbers beyond 9 are
+    /// written as letters (`A` = 10, `B` = 11, ...) and any other character is an empty cell.
+    ///
+    /// The grid can be followed, after an empty line, by these directives:
+    /// - `boxes RxC`: the boxes are R rows high and C columns wide instead of the guessed shape
+    /// - `regions`: followed by one line per row and one character per cell, cells with the
+    ///   same character belong to the same region (jigsaw sudoku)
+    /// - `latin-square`: the board has no boxes, only rows and columns cannot repeat a number
+    /// - `diagonal`: the two main diagonals must contain every number once (X-Sudoku)
+    /// - `hyper`: the windows between the boxes must contain every number once (Hyper Sudoku)
+    /// - `extra-region CELLS...`: the cells, as many as the rows, must contain every number
+    ///   once
+    /// - `anti-knight`, `anti-king`: cells a chess knight's (or king's) move apart cannot
+    ///   hold the same number
+    /// - `non-consecutive`: orthogonally adjacent cells cannot hold consecutive numbers
+    /// - `kropki white CELL CELL`, `kropki black CELL CELL`: a white (consecutive numbers) or
+    ///   black (one number is the double of the other) dot between two adjacent cells
+    /// - `kropki negative`: adjacent cells without a dot satisfy neither dot
+    /// - `xv x CELL CELL`, `xv v CELL CELL`: the numbers of two adjacent cells add up to 10
+    ///   (or 5)
+    /// - `xv negative`: adjacent cells without a marker add up to neither 10 nor 5
+    /// - `inequality CELL < CELL`, `inequality CELL > CELL`: an inequality sign between two
+    ///   adjacent cells (greater-than sudoku)
+    /// - `thermo CELLS...`: the numbers strictly increase along the cells, starting from the
+    ///   bulb in the first one
+    /// - `sandwich rows S1 S2 ...`, `sandwich columns S1 S2 ...`: the sums between the lowest
+    ///   and the highest number of each row (or column), `.` when a line has no clue
+    /// - `arrow CIRCLE CELLS...`: the number in the circle is the sum of the numbers along
+    ///   the cells of the arrow
+    /// - `even CELLS...`, `odd CELLS...`: the cells can only hold even (or odd) numbers
+    /// - `cage S CELLS...`: the cells, written as `r1c1` (row 1, column 1), must add up to S
+    ///   without repeating a number (killer sudoku)
+    /// - `little-killer S CELL DIRECTION`: the numbers along the diagonal starting from the
+    ///   cell at the edge of the grid add up to S, the direction is one of `down-right`,
+    ///   `down-left`, `up-right` and `up-left`
+    ///
+    /// Lines starting with `#` are comments, such as the seed of a generated puzzle.
+    ///
+    /// Files ending in `.toml` or `.json` describe the puzzle instead, with the `size` of the
+    /// grid (or its `givens`, one string per row), the `boxes` shape and a list of
+    /// `constraints`. Each constraint has a `type` named as the directives above and its
+    /// cells as a list, e.g. `{ type = "cage", sum = 10, cells = ["r1c1", "r1c2"] }`
     pub fn from_file(path: &str) -> Self {
-        let mut board = Array2::from_elem((9, 9), 0_u8);
+        Self::from_file_with_alphabet(path, Alphabet::default())
+    }
+
+    /// same as `from_file`, but the numbers are written with the symbols of `alphabet`.
+    /// Files ending in `.toml` or `.json` are read as puzzle descriptions, see `puzzle_file`
+    pub fn from_file_with_alphabet(path: &str, alphabet: Alphabet) -> Self {
+        Self::try_from_file_with_alphabet(path, alphabet)
+            .unwrap_or_else(|error| panic!("{}", error))
+    }
+
+    /// same as `from_file_with_alphabet`, but a malformed puzzle description gives an error
+    /// instead of a panic
+    pub fn try_from_file_with_alphabet(path: &str, alphabet: Alphabet) -> Result<Self, String> {
         let mut file = File::open(path).unwrap();
         let mut contents = String::new();
         file.read_to_string(&mut contents).unwrap();
-        let mut row = 0;
-        for line in contents.lines() {
-            let mut col = 0;
-            for c in line.chars() {
-                if c.is_digit(10) {
-                    board[[row, col]] = c.to_digit(10).unwrap() as u8;
+        match Path::new(path)
+            .extension()
+            .and_then(|extension| extension.to_str())
+        {
+            Some("toml") => puzzle_file::from_toml(&contents, alphabet),
+            Some("json") => puzzle_file::from_json(&contents, alphabet),
+            _ => Ok(Self::from_text(&contents, alphabet)),
+        }
+    }
+
+    /// the grid written as in board files, one line per row. Empty cells are written as `0`,
+    /// or as `.` when 0 is a symbol of the alphabet
+    pub fn grid_text(&self) -> String {
+        let empty = match self.alphabet.value_of('0') {
+            Some(_) => '.',
+            None => '0',
+        };
+        self.values
+            .rows()
+            .into_iter()
+            .map(|row| {
+                let line: String = row
+                    .iter()
+                    .map(|&value| match value {
+                        0 => empty,
+                        _ => self.alphabet.symbol_of(value),
+                    })
+                    .collect();
+                line + "\n"
+            })
+            .collect()
+    }
+
+    /// the board written as in `from_file`
+    fn from_text(contents: &str, alphabet: Alphabet) -> Self {
+        let mut lines = contents.lines();
+        let grid_lines: Vec<&str> = lines
+            .by_ref()
+            .take_while(|line| !line.trim().is_empty())
+            .collect();
+        let size = grid_lines.len();
+        let board = parse_grid(&grid_lines, &alphabet);
+
+        let (box_rows, box_cols) = box_shape_of(size);
+        let mut regions = box_regions(size, box_rows, box_cols);
+        let mut latin_square = false;
+        let mut diagonal = false;
+        let mut hyper = false;
+        let mut extra_regions = Vec::new();
+        let mut anti_knight = false;
+        let mut anti_king = false;
+        let mut non_consecutive = false;
+        let mut dots = Vec::new();
+        let mut negative_kropki = false;
+        let mut xvs = Vec::new();
+        let mut negative_xv = false;
+        let mut inequalities = Vec::new();
+        let mut thermos = Vec::new();
+        let mut row_sandwiches = vec![None; size];
+        let mut column_sandwiches = vec![None; size];
+        let mut arrows = Vec::new();
+        let mut parities = Vec::new();
+        let mut cages = Vec::new();
+        let mut little_killers = Vec::new();
+        while let Some(line) = lines.next() {
+            let line = line.trim();
+            let (directive, argument) = line.split_once(' ').unwrap_or((line, ""));
+            match directive {
+                "" => (),
+                _ if directive.starts_with('#') => (),
+                "boxes" => {
+                    let (box_rows, box_cols) = parse_box_shape(argument.trim());
+                    regions = box_regions(size, box_rows, box_cols);
+                }
+                "regions" => regions = parse_regions(lines.by_ref().take(size).collect(), size),
+                "latin-square" => latin_square = true,
+                "diagonal" => diagonal = true,
+                "hyper" => hyper = true,
+                "extra-region" => extra_regions.push(parse_cells(argument)),
+                "anti-knight" => anti_knight = true,
+                "anti-king" => anti_king = true,
+                "non-consecutive" => non_consecutive = true,
+                "kropki" => match argument.trim().split_once(' ') {
+                    Some(("white", edge)) => dots.push((parse_edge(edge), Dot::White)),
+                    Some(("black", edge)) => dots.push((parse_edge(edge), Dot::Black)),
+                    None if argument.trim() == "negative" => negative_kropki = true,
+                    _ => panic!("unknown kropki directive: {}", line),
+                },
+                "xv" => match argument.trim().split_once(' ') {
+                    Some(("x", edge)) => xvs.push((parse_edge(edge), Xv::X)),
+                    Some(("v", edge)) => xvs.push((parse_edge(edge), Xv::V)),
+                    None if argument.trim() == "negative" => negative_xv = true,
+                    _ => panic!("unknown xv directive: {}", line),
+                },
+                "inequality" => inequalities.push(parse_inequality(argument)),
+                "thermo" => thermos.push(Thermo::new(parse_cells(argument))),
+                "sandwich" => match argument.trim().split_once(' ') {
+                    Some(("rows", sums)) => row_sandwiches = parse_sandwiches(sums, size),
+                    Some(("columns", sums)) => column_sandwiches = parse_sandwiches(sums, size),
+                    _ => panic!("unknown sandwich directive: {}", line),
+                },
+                "arrow" => arrows.push(parse_arrow(argument)),
+                "even" => {
+                    parities.extend(parse_cells(argument).into_iter().map(|c| (c, Parity::Even)))
+                }
+                "odd" => {
+                    parities.extend(parse_cells(argument).into_iter().map(|c| (c, Parity::Odd)))
                 }
-                col += 1;
+                "cage" => cages.push(parse_cage(argument)),
+                "little-killer" => little_killers.push(parse_little_killer(argument)),
+                _ => panic!("unknown directive: {}", line),
             }
-            row += 1;
         }
-        Board { values: board }
+        let mut board = Self::new_with_regions(board, regions);
+        board.alphabet = alphabet;
+        board.latin_square = latin_square;
+        board.diagonal = diagonal;
+        board.set_hyper(hyper);
+        for cells in extra_regions {
+            board.add_extra_region(cells);
+        }
+        board.anti_knight = anti_knight;
+        board.anti_king = anti_king;
+        board.non_consecutive = non_consecutive;
+        board.negative_kropki = negative_kropki;
+        for (edge, dot) in dots {
+            board.add_dot(edge, dot);
+        }
+        board.negative_xv = negative_xv;
+        for (edge, xv) in xvs {
+            board.add_xv(edge, xv);
+        }
+        for (edge, ordering) in inequalities {
+            board.add_inequality(edge, ordering);
+        }
+        for thermo in thermos {
+            board.add_thermo(thermo);
+        }
+        board.row_sandwiches = row_sandwiches;
+        board.column_sandwiches = column_sandwiches;
+        for arrow in arrows {
+            board.add_arrow(arrow);
+        }
+        for ((row, col), parity) in parities {
+            board.set_parity(row, col, Some(parity));
+        }
+        for cage in cages {
+            board.add_cage(cage);
+        }
+        for little_killer in little_killers {
+            board.add_little_killer(little_killer);
+        }
+        board
     }
 
     pub fn from_board_dir(path: &str) -> Self {
@@ -44,78 +391,875 @@ impl Board {
         Self::from_file(board_path.as_path().join(path).to_str().unwrap())
     }
 
-    fn check_row(&self, row: usize) -> bool {
-        let mut elem_checked: [bool; 9] = [false; 9];
-        for elem in self.values.slice(s![row, ..]).iter() {
-            if *elem == 0 {
-                continue;
-            } else if !elem_checked[*elem as usize - 1] {
-                elem_checked[*elem as usize - 1] = true;
-            } else {
-                return false;
+    /// number of rows (and columns) of the board, which is also the highest number it holds
+    pub fn size(&self) -> usize {
+        self.values.nrows()
+    }
+
+    pub fn alphabet(&self) -> &Alphabet {
+        &self.alphabet
+    }
+
+    /// change the symbols used to print the board
+    pub fn set_alphabet(&mut self, alphabet: Alphabet) {
+        check_alphabet_size(&alphabet, self.size());
+        self.alphabet = alphabet;
+    }
+
+    pub fn is_latin_square(&self) -> bool {
+        self.latin_square
+    }
+
+    /// turn the Latin square mode on or off, the regions are kept but they only count when it
+    /// is off
+    pub fn set_latin_square(&mut self, latin_square: bool) {
+        self.latin_square = latin_square;
+    }
+
+    pub fn is_diagonal(&self) -> bool {
+        self.diagonal
+    }
+
+    /// turn the X-Sudoku rule on or off
+    pub fn set_diagonal(&mut self, diagonal: bool) {
+        self.diagonal = diagonal;
+    }
+
+    pub fn is_hyper(&self) -> bool {
+        self.hyper
+    }
+
+    /// turn the Hyper Sudoku rule on or off, the windows take the shape of the boxes so the
+    /// regions must be boxes
+    pub fn set_hyper(&mut self, hyper: bool) {
+        if hyper && self.box_shape().is_none() {
+            panic!("the windows of a Hyper Sudoku need a board made of boxes");
+        }
+        self.hyper = hyper;
+    }
+
+    pub fn extra_regions(&self) -> &[Vec<(usize, usize)>] {
+        &self.extra_regions
+    }
+
+    /// add a group of cells that must contain every number once, panics unless they are as
+    /// many as the rows, all different and inside the board
+    pub fn add_extra_region(&mut self, cells: Vec<(usize, usize)>) {
+        let size = self.size();
+        if cells.len() != size {
+            panic!(
+                "a region must have {} cells instead of {}",
+                size,
+                cells.len()
+            );
+        }
+        for (i, &(row, col)) in cells.iter().enumerate() {
+            if row >= size || col >= size {
+                panic!("cell {:?} of the region is outside the board", (row, col));
+            }
+            if cells[..i].contains(&(row, col)) {
+                panic!("cell {:?} is repeated in the region", (row, col));
             }
         }
-        true
+        self.extra_regions.push(cells);
+    }
+
+    pub fn is_anti_knight(&self) -> bool {
+        self.anti_knight
+    }
+
+    /// turn the anti-knight rule on or off
+    pub fn set_anti_knight(&mut self, anti_knight: bool) {
+        self.anti_knight = anti_knight;
+    }
+
+    pub fn is_anti_king(&self) -> bool {
+        self.anti_king
+    }
+
+    /// turn the anti-king rule on or off
+    pub fn set_anti_king(&mut self, anti_king: bool) {
+        self.anti_king = anti_king;
+    }
+
+    pub fn is_non_consecutive(&self) -> bool {
+        self.non_consecutive
+    }
+
+    /// turn the non-consecutive rule on or off
+    pub fn set_non_consecutive(&mut self, non_consecutive: bool) {
+        self.non_consecutive = non_consecutive;
+    }
+
+    pub fn dots(&self) -> &[(Edge, Dot)] {
+        &self.dots
+    }
+
+    /// add a Kropki dot between two adjacent cells, given in any order. Panics if the cells
+    /// are not adjacent or already have a dot between them
+    pub fn add_dot(&mut self, edge: Edge, dot: Dot) {
+        let edge = self.check_edge(edge);
+        if self.dot_of(edge).is_some() {
+            panic!("cells {:?} and {:?} already have a dot", edge.0, edge.1);
+        }
+        self.dots.push((edge, dot));
+    }
+
+    pub fn is_negative_kropki(&self) -> bool {
+        self.negative_kropki
+    }
+
+    /// turn the negative Kropki rule on or off
+    pub fn set_negative_kropki(&mut self, negative_kropki: bool) {
+        self.negative_kropki = negative_kropki;
+    }
+
+    pub fn xvs(&self) -> &[(Edge, Xv)] {
+        &self.xvs
+    }
+
+    /// add a XV marker between two adjacent cells, given in any order. Panics if the cells
+    /// are not adjacent or already have a marker between them
+    pub fn add_xv(&mut self, edge: Edge, xv: Xv) {
+        let edge = self.check_edge(edge);
+        if self.xv_of(edge).is_some() {
+            panic!("cells {:?} and {:?} already have a marker", edge.0, edge.1);
+        }
+        self.xvs.push((edge, xv));
+    }
+
+    pub fn is_negative_xv(&self) -> bool {
+        self.negative_xv
+    }
+
+    /// turn the negative XV rule on or off
+    pub fn set_negative_xv(&mut self, negative_xv: bool) {
+        self.negative_xv = negative_xv;
+    }
+
+    pub fn inequalities(&self) -> &[(Edge, Ordering)] {
+        &self.inequalities
+    }
+
+    /// add an inequality sign between two adjacent cells, `ordering` compares the first cell
+    /// with the second one. Panics if the cells are not adjacent or already have a sign
+    pub fn add_inequality(&mut self, edge: Edge, ordering: Ordering) {
+        if ordering == Ordering::Equal {
+            panic!("adjacent cells in a row, column or box cannot be equal");
+        }
+        let sorted = self.check_edge(edge);
+        let ordering = match sorted == edge {
+            true => ordering,
+            false => ordering.reverse(),
+        };
+        if self.inequality_of(sorted).is_some() {
+            panic!(
+                "cells {:?} and {:?} already have a sign",
+                sorted.0, sorted.1
+            );
+        }
+        self.inequalities.push((sorted, ordering));
+    }
+
+    pub fn thermos(&self) -> &[Thermo] {
+        &self.thermos
     }
 
-    fn check_column(&self, col: usize) -> bool {
-        let mut elem_checked: [bool; 9] = [false; 9];
-        for elem in self.values.slice(s![.., col]).iter() {
-            if *elem == 0 {
-                continue;
-            } else if !elem_checked[*elem as usize - 1] {
-                elem_checked[*elem as usize - 1] = true;
-            } else {
-                return false;
+    /// add a thermometer, panics if it does not fit the board
+    pub fn add_thermo(&mut self, thermo: Thermo) {
+        let size = self.size();
+        if thermo.cells().len() > size {
+            panic!("a thermometer cannot have more than {} cells", size);
+        }
+        for &(row, col) in thermo.cells() {
+            if row >= size || col >= size {
+                panic!(
+                    "cell {:?} of the thermometer is outside the board",
+                    (row, col)
+                );
             }
         }
-        true
+        self.thermos.push(thermo);
     }
 
-    fn check_square(&self, row: usize, col: usize) -> bool {
-        let mut elem_checked: [bool; 9] = [false; 9];
-        let row_start = row * 3;
-        let col_start = col * 3;
-        for i in 0..3 {
-            for j in 0..3 {
-                let elem = self.values[(row_start + i, col_start + j)];
-                if elem == 0 {
-                    continue;
-                } else if !elem_checked[elem as usize - 1] {
-                    elem_checked[elem as usize - 1] = true;
-                } else {
-                    return false;
+    pub fn row_sandwiches(&self) -> &[Option<u32>] {
+        &self.row_sandwiches
+    }
+
+    pub fn column_sandwiches(&self) -> &[Option<u32>] {
+        &self.column_sandwiches
+    }
+
+    /// set or remove the sandwich clue of a row
+    pub fn set_row_sandwich(&mut self, row: usize, sum: Option<u32>) {
+        self.row_sandwiches[row] = sum;
+    }
+
+    /// set or remove the sandwich clue of a column
+    pub fn set_column_sandwich(&mut self, col: usize, sum: Option<u32>) {
+        self.column_sandwiches[col] = sum;
+    }
+
+    pub fn arrows(&self) -> &[Arrow] {
+        &self.arrows
+    }
+
+    /// add an arrow, panics if it does not fit the board
+    pub fn add_arrow(&mut self, arrow: Arrow) {
+        let size = self.size();
+        for &(row, col) in [arrow.circle()].iter().chain(arrow.cells()) {
+            if row >= size || col >= size {
+                panic!("cell {:?} of the arrow is outside the board", (row, col));
+            }
+        }
+        self.arrows.push(arrow);
+    }
+
+    pub fn parity_of(&self, row: usize, col: usize) -> Option<Parity> {
+        self.parities[(row, col)]
+    }
+
+    /// restrict the cell to even or odd numbers, or remove its restriction with `None`
+    pub fn set_parity(&mut self, row: usize, col: usize, parity: Option<Parity>) {
+        self.parities[(row, col)] = parity;
+    }
+
+    pub fn cages(&self) -> &[Cage] {
+        &self.cages
+    }
+
+    /// add a killer sudoku cage, panics if the cage does not fit the board
+    /// or overlaps another one
+    pub fn add_cage(&mut self, cage: Cage) {
+        let size = self.size();
+        if cage.cells().len() > size {
+            panic!("a cage cannot have more than {} cells", size);
+        }
+        for &(row, col) in cage.cells() {
+            if row >= size || col >= size {
+                panic!("cell {:?} of the cage is outside the board", (row, col));
+            }
+            if self.cage_of(row, col).is_some() {
+                panic!("cell {:?} is already in a cage", (row, col));
+            }
+        }
+        self.cages.push(cage);
+    }
+
+    pub fn little_killers(&self) -> &[LittleKiller] {
+        &self.little_killers
+    }
+
+    /// add a little killer clue, panics unless its diagonal starts at the edge of the board
+    pub fn add_little_killer(&mut self, little_killer: LittleKiller) {
+        let size = self.size();
+        let (row, col) = little_killer.start();
+        if row >= size || col >= size {
+            panic!(
+                "cell {:?} of the little killer is outside the board",
+                (row, col)
+            );
+        }
+        if !little_killer.starts_at_edge(size) {
+            panic!(
+                "the diagonal of the little killer does not start at the edge in {:?}",
+                (row, col)
+            );
+        }
+        self.little_killers.push(little_killer);
+    }
+
+    /// the dot on the edge, if any
+    fn dot_of(&self, edge: Edge) -> Option<Dot> {
+        self.dots
+            .iter()
+            .find(|(dot_edge, _)| *dot_edge == edge)
+            .map(|&(_, dot)| dot)
+    }
+
+    /// the XV marker on the edge, if any
+    fn xv_of(&self, edge: Edge) -> Option<Xv> {
+        self.xvs
+            .iter()
+            .find(|(xv_edge, _)| *xv_edge == edge)
+            .map(|&(_, xv)| xv)
+    }
+
+    /// the inequality sign on the edge, if any
+    fn inequality_of(&self, edge: Edge) -> Option<Ordering> {
+        self.inequalities
+            .iter()
+            .find(|(sign_edge, _)| *sign_edge == edge)
+            .map(|&(_, ordering)| ordering)
+    }
+
+    /// the edge with its cells in order, panics if they are outside the board or not adjacent
+    fn check_edge(&self, (first, second): Edge) -> Edge {
+        let size = self.size();
+        for (row, col) in [first, second] {
+            if row >= size || col >= size {
+                panic!("cell {:?} is outside the board", (row, col));
+            }
+        }
+        if first.0.abs_diff(second.0) + first.1.abs_diff(second.1) != 1 {
+            panic!("cells {:?} and {:?} are not adjacent", first, second);
+        }
+        (first.min(second), first.max(second))
+    }
+
+    /// every pair of orthogonally adjacent cells, row by row
+    fn edges(&self) -> Vec<Edge> {
+        let size = self.size();
+        let mut edges = Vec::new();
+        for i in 0..size {
+            for j in 0..size {
+                if j + 1 < size {
+                    edges.push(((i, j), (i, j + 1)));
+                }
+                if i + 1 < size {
+                    edges.push(((i, j), (i + 1, j)));
                 }
             }
         }
-        true
+        edges
     }
 
-    fn check_complete(&self) -> bool {
-        for i in 0..9 {
-            if !self.check_row(i) {
-                return false;
+    /// index of the cage containing the cell, if any
+    fn cage_of(&self, row: usize, col: usize) -> Option<usize> {
+        self.cages
+            .iter()
+            .position(|cage| cage.cells().contains(&(row, col)))
+    }
+
+    /// whether the cell lays on one of the two main diagonals
+    fn on_diagonal(&self, row: usize, col: usize) -> bool {
+        row == col || row + col == self.size() - 1
+    }
+
+    fn row_cells(&self, row: usize) -> Vec<(usize, usize)> {
+        (0..self.size()).map(|col| (row, col)).collect()
+    }
+
+    fn column_cells(&self, col: usize) -> Vec<(usize, usize)> {
+        (0..self.size()).map(|row| (row, col)).collect()
+    }
+
+    /// cells belonging to `region`, row by row
+    fn region_cells(&self, region: usize) -> Vec<(usize, usize)> {
+        self.regions
+            .indexed_iter()
+            .filter(|(_, &r)| r == region)
+            .map(|(pos, _)| pos)
+            .collect()
+    }
+
+    /// cells of the main diagonal and of the anti-diagonal, both from the top row
+    fn diagonal_cells(&self) -> Vec<Vec<(usize, usize)>> {
+        let size = self.size();
+        vec![
+            (0..size).map(|i| (i, i)).collect(),
+            (0..size).map(|i| (i, size - 1 - i)).collect(),
+        ]
+    }
+
+    /// rows and columns of the boxes the regions are made of, `None` when they are not boxes
+    /// (e.g. a jigsaw sudoku)
+    fn box_shape(&self) -> Option<(usize, usize)> {
+        let size = self.size();
+        let box_rows = (0..size)
+            .take_while(|&i| self.regions[(i, 0)] == self.regions[(0, 0)])
+            .count();
+        if !size.is_multiple_of(box_rows) {
+            return None;
+        }
+        let box_cols = size / box_rows;
+        // every region has as many cells as a box, so each box being a single region is enough
+        let is_box = |(i, j): (usize, usize), &region: &usize| {
+            region == self.regions[(i - i % box_rows, j - j % box_cols)]
+        };
+        match self
+            .regions
+            .indexed_iter()
+            .all(|(pos, region)| is_box(pos, region))
+        {
+            true => Some((box_rows, box_cols)),
+            false => None,
+        }
+    }
+
+    /// cells of the windows of a Hyper Sudoku: boxes shifted by one cell from the top left
+    /// corner and spaced by one cell from each other, so that 9x9 boards have four of them
+    fn window_cells(&self) -> Vec<Vec<(usize, usize)>> {
+        let size = self.size();
+        let (box_rows, box_cols) = self
+            .box_shape()
+            .expect("the windows of a Hyper Sudoku need a board made of boxes");
+        let starts = |len: usize| {
+            (0..)
+                .map(move |k| 1 + k * (len + 1))
+                .take_while(move |start| start + len < size)
+        };
+        let mut windows = Vec::new();
+        for top in starts(box_rows) {
+            for left in starts(box_cols) {
+                windows.push(
+                    (top..top + box_rows)
+                        .flat_map(|i| (left..left + box_cols).map(move |j| (i, j)))
+                        .collect(),
+                );
+            }
+        }
+        windows
+    }
+
+    /// the units every board comes with: its rows, columns and regions (unless it is a Latin
+    /// square), followed by the
+    /// diagonals of a X-Sudoku, the windows of a Hyper Sudoku and the extra regions
+    fn builtin_units(&self) -> Vec<Unit> {
+        let size = self.size();
+        let mut units: Vec<Unit> = (0..size)
+            .map(|i| Unit::new(&format!("row {}", i + 1), self.row_cells(i)))
+            .collect();
+        units.extend(
+            (0..size).map(|j| Unit::new(&format!("column {}", j + 1), self.column_cells(j))),
+        );
+        if !self.latin_square {
+            units.extend(
+                (0..size).map(|r| Unit::new(&format!("region {}", r + 1), self.region_cells(r))),
+            );
+        }
+        if self.diagonal {
+            let names = ["main diagonal", "anti-diagonal"];
+            for (name, cells) in names.iter().zip(self.diagonal_cells()) {
+                units.push(Unit::new(name, cells));
+            }
+        }
+        if self.hyper {
+            for (w, cells) in self.window_cells().into_iter().enumerate() {
+                units.push(Unit::new(&format!("window {}", w + 1), cells));
+            }
+        }
+        for (e, cells) in self.extra_regions.iter().enumerate() {
+            units.push(Unit::new(&format!("extra region {}", e + 1), cells.clone()));
+        }
+        units
+    }
+
+    /// every rule the board is checked against: the built-in units, the rules of the
+    /// variants that are on and the constraints added with `add_constraint`
+    pub fn constraints(&self) -> Vec<Arc<dyn Constraint>> {
+        fn shared(constraint: impl Constraint + 'static) -> Arc<dyn Constraint> {
+            Arc::new(constraint)
+        }
+        let mut constraints: Vec<Arc<dyn Constraint>> =
+            self.builtin_units().into_iter().map(shared).collect();
+        if self.anti_knight {
+            constraints.push(shared(ChessMoves {
+                piece: "knight",
+                moves: KNIGHT_MOVES,
+            }));
+        }
+        if self.anti_king {
+            constraints.push(shared(ChessMoves {
+                piece: "king",
+                moves: KING_MOVES,
+            }));
+        }
+        constraints.extend(self.relations().into_iter().map(shared));
+        constraints.extend(self.thermos.iter().cloned().map(shared));
+        constraints.extend(self.sandwiches().into_iter().map(shared));
+        constraints.extend(self.arrows.iter().cloned().map(shared));
+        let parities = self.parities.indexed_iter();
+        constraints.extend(parities.filter_map(|(pos, &parity)| Some(shared((pos, parity?)))));
+        constraints.extend(self.sums().into_iter().map(shared));
+        constraints.extend(self.custom_constraints.iter().cloned());
+        constraints
+    }
+
+    /// the constraints added with `add_constraint`
+    pub fn custom_constraints(&self) -> &[Arc<dyn Constraint>] {
+        &self.custom_constraints
+    }
+
+    /// add a rule of our own, it is checked along with the rules of the board and the solver
+    /// prunes the candidates with it
+    pub fn add_constraint(&mut self, constraint: impl Constraint + 'static) {
+        self.custom_constraints.push(Arc::new(constraint));
+    }
+
+    /// every group of cells that cannot repeat a number, including the cages.
+    /// A cell can belong to any number of units
+    pub(crate) fn units(&self) -> Vec<Vec<(usize, usize)>> {
+        let mut units: Vec<Vec<(usize, usize)>> = self
+            .builtin_units()
+            .iter()
+            .map(|unit| unit.cells().to_vec())
+            .collect();
+        units.extend(self.cages.iter().map(|cage| cage.cells().to_vec()));
+        units
+    }
+
+    /// cells a knight's or king's move away from the cell, following the chess rules that
+    /// are on. They cannot hold the number of the cell but, unlike units, they are not
+    /// required to hold every number
+    pub(crate) fn chess_peers(&self, row: usize, col: usize) -> Vec<(usize, usize)> {
+        let mut moves: Vec<(isize, isize)> = Vec::new();
+        if self.anti_knight {
+            moves.extend(KNIGHT_MOVES);
+        }
+        if self.anti_king {
+            moves.extend(KING_MOVES);
+        }
+        let size = self.size() as isize;
+        moves
+            .into_iter()
+            .map(|(di, dj)| (row as isize + di, col as isize + dj))
+            .filter(|&(i, j)| (0..size).contains(&i) && (0..size).contains(&j))
+            .map(|(i, j)| (i as usize, j as usize))
+            .collect()
+    }
+
+    /// pairs of cells whose numbers are bound by a relation, such as the orthogonally
+    /// adjacent cells of a non-consecutive sudoku, the dots of a Kropki sudoku or the signs of a
+    /// greater-than sudoku
+    pub(crate) fn relations(&self) -> Vec<Link> {
+        let mut relations: Vec<Link> = self
+            .dots
+            .iter()
+            .map(|&((first, second), dot)| (first, second, dot.relation()))
+            .collect();
+        for &((first, second), ordering) in self.inequalities.iter() {
+            match ordering {
+                Ordering::Less => relations.push((first, second, Relation::Less)),
+                _ => relations.push((second, first, Relation::Less)),
+            }
+        }
+        for (first, second) in self.edges() {
+            if self.non_consecutive {
+                relations.push((first, second, Relation::NonConsecutive));
             }
-            if !self.check_column(i) {
-                return false;
+            if self.negative_kropki && self.dot_of((first, second)).is_none() {
+                relations.push((first, second, Relation::NoDot));
             }
-            if !self.check_square(i / 3, i % 3) {
-                return false;
+            if self.negative_xv && self.xv_of((first, second)).is_none() {
+                relations.push((first, second, Relation::NoXv));
             }
         }
-        true
+        relations
+    }
+
+    fn check_complete(&self) -> bool {
+        self.constraints()
+            .iter()
+            .all(|constraint| constraint.check(&self.values))
+    }
+
+    /// the rows and columns with a sandwich clue
+    pub(crate) fn sandwiches(&self) -> Vec<Sandwich> {
+        let rows = self.row_sandwiches.iter().enumerate();
+        let rows = rows.filter_map(|(i, &sum)| Some((sum?, self.row_cells(i))));
+        let columns = self.column_sandwiches.iter().enumerate();
+        let columns = columns.filter_map(|(j, &sum)| Some((sum?, self.column_cells(j))));
+        rows.chain(columns)
+            .map(|(sum, cells)| Sandwich { sum, cells })
+            .collect()
+    }
+
+    /// groups of cells adding up to a sum: the cages of a killer sudoku, the pairs of a XV
+    /// sudoku and the diagonals of the little killer clues
+    pub(crate) fn sums(&self) -> Vec<CellSum> {
+        let size = self.size();
+        let xvs = self.xvs.iter().map(|&((first, second), xv)| CellSum {
+            sum: xv.sum(),
+            cells: vec![first, second],
+            distinct: true,
+        });
+        let little_killers = self.little_killers.iter().map(|little_killer| CellSum {
+            sum: little_killer.sum(),
+            cells: little_killer.cells(size),
+            distinct: false,
+        });
+        self.cages
+            .iter()
+            .map(Cage::as_sum)
+            .chain(xvs)
+            .chain(little_killers)
+            .collect()
     }
 
     fn is_complete(&self) -> bool {
-        for i in 0..9 {
-            for j in 0..9 {
-                if self.values[(i, j)] == 0 {
-                    return false;
+        self.values.iter().all(|&x| x != 0)
+    }
+}
+
+const KNIGHT_MOVES: [(isize, isize); 8] = [
+    (-2, -1),
+    (-2, 1),
+    (-1, -2),
+    (-1, 2),
+    (1, -2),
+    (1, 2),
+    (2, -1),
+    (2, 1),
+];
+
+const KING_MOVES: [(isize, isize); 8] = [
+    (-1, -1),
+    (-1, 0),
+    (-1, 1),
+    (0, -1),
+    (0, 1),
+    (1, -1),
+    (1, 0),
+    (1, 1),
+];
+
+/// the most square shape of boxes fitting a board with `size` rows, boxes are never taller
+/// than wide (2x3 for a 6x6 board, 3x4 for a 12x12 board, 4x4 for a 16x16 board)
+fn box_shape_of(size: usize) -> (usize, usize) {
+    let box_rows = (1..=size)
+        .take_while(|rows| rows * rows <= size)
+        .filter(|rows| size.is_multiple_of(*rows))
+        .last()
+        .unwrap_or(1);
+    (box_rows, size / box_rows)
+}
+
+fn check_alphabet_size(alphabet: &Alphabet, size: usize) {
+    if alphabet.len() < size {
+        panic!(
+            "an alphabet of {} symbols cannot write a {}x{} board",
+            alphabet.len(),
+            size,
+            size
+        );
+    }
+}
+
+/// parse the numbers of a grid, one line per row and one character per cell,
+/// the characters that are not symbols of `alphabet` are empty cells
+fn parse_grid(lines: &[&str], alphabet: &Alphabet) -> Array2<u8> {
+    let size = lines.len();
+    check_alphabet_size(alphabet, size);
+    let mut board = Array2::from_elem((size, size), 0_u8);
+    for (row, line) in lines.iter().enumerate() {
+        for (col, c) in line.chars().enumerate() {
+            if let Some(value) = alphabet.value_of(c) {
+                if value as usize > size {
+                    panic!("{} is not a valid number on a {}x{} board", c, size, size);
                 }
+                board[[row, col]] = value;
             }
         }
-        true
     }
+    board
+}
+
+/// parse a cell written as `r1c1`, rows and columns are numbered from 1
+fn parse_cell(cell: &str) -> (usize, usize) {
+    try_parse_cell(cell).unwrap_or_else(|| panic!("{} is not a valid cell", cell))
+}
+
+/// same as `parse_cell`, but `None` when the cell is not valid
+fn try_parse_cell(cell: &str) -> Option<(usize, usize)> {
+    cell.to_ascii_lowercase()
+        .strip_prefix('r')
+        .and_then(|cell| cell.split_once('c'))
+        .and_then(|(row, col)| Some((row.parse::<usize>().ok()?, col.parse::<usize>().ok()?)))
+        .filter(|&(row, col)| row > 0 && col > 0)
+        .map(|(row, col)| (row - 1, col - 1))
+}
+
+/// parse two adjacent cells, e.g. `r1c1 r1c2`
+fn parse_edge(edge: &str) -> Edge {
+    match parse_cells(edge)[..] {
+        [first, second] => (first, second),
+        _ => panic!("{} is not a pair of cells", edge),
+    }
+}
+
+/// parse cells separated by spaces, e.g. `r1c1 r1c2 r2c2`
+fn parse_cells(cells: &str) -> Vec<(usize, usize)> {
+    cells.split_whitespace().map(parse_cell).collect()
+}
+
+/// parse the sandwich clues of all the rows (or columns), `.` stands for no clue
+fn parse_sandwiches(sums: &str, size: usize) -> Vec<Option<u32>> {
+    let sums: Vec<Option<u32>> = sums
+        .split_whitespace()
+        .map(|sum| match sum {
+            "." => None,
+            _ => Some(
+                sum.parse()
+                    .unwrap_or_else(|_| panic!("{} is not a valid sandwich clue", sum)),
+            ),
+        })
+        .collect();
+    if sums.len() != size {
+        panic!(
+            "there are {} sandwich clues instead of {}",
+            sums.len(),
+            size
+        );
+    }
+    sums
+}
+
+/// parse an arrow written as its circle followed by its cells, e.g. `r1c1 r1c2 r1c3`
+fn parse_arrow(arrow: &str) -> Arrow {
+    match parse_cells(arrow).split_first() {
+        Some((&circle, cells)) => Arrow::new(circle, cells.to_vec()),
+        None => panic!("an arrow must have a circle"),
+    }
+}
+
+/// parse an inequality between two adjacent cells, e.g. `r1c1 < r1c2`
+fn parse_inequality(inequality: &str) -> (Edge, Ordering) {
+    match inequality.split_whitespace().collect::<Vec<_>>()[..] {
+        [first, "<", second] => ((parse_cell(first), parse_cell(second)), Ordering::Less),
+        [first, ">", second] => ((parse_cell(first), parse_cell(second)), Ordering::Greater),
+        _ => panic!("{} is not a valid inequality", inequality),
+    }
+}
+
+/// parse a cage written as its sum followed by its cells, e.g. `10 r1c1 r1c2 r2c1`
+fn parse_cage(cage: &str) -> Cage {
+    let (sum, cells) = cage.trim().split_once(' ').unwrap_or((cage, ""));
+    let sum = sum
+        .parse()
+        .unwrap_or_else(|_| panic!("{} does not start with the sum of the cage", cage));
+    Cage::new(sum, parse_cells(cells))
+}
+
+/// parse a little killer written as its sum, its first cell and its direction,
+/// e.g. `15 r1c7 down-right`
+fn parse_little_killer(little_killer: &str) -> LittleKiller {
+    match little_killer.split_whitespace().collect::<Vec<_>>()[..] {
+        [sum, start, direction] => {
+            let sum = sum
+                .parse()
+                .unwrap_or_else(|_| panic!("{} is not a valid little killer sum", sum));
+            LittleKiller::new(sum, parse_cell(start), parse_direction(direction))
+        }
+        _ => panic!("{} is not a valid little killer", little_killer),
+    }
+}
+
+/// parse the direction of a little killer, e.g. `down-right`
+fn parse_direction(direction: &str) -> Direction {
+    try_parse_direction(direction)
+        .unwrap_or_else(|| panic!("{} is not a diagonal direction", direction))
+}
+
+/// same as `parse_direction`, but `None` when it is not a diagonal direction
+fn try_parse_direction(direction: &str) -> Option<Direction> {
+    match direction {
+        "down-right" => Some(Direction::DownRight),
+        "down-left" => Some(Direction::DownLeft),
+        "up-right" => Some(Direction::UpRight),
+        "up-left" => Some(Direction::UpLeft),
+        _ => None,
+    }
+}
+
+/// regions made of boxes of `box_rows` x `box_cols` cells, numbered row by row
+fn box_regions(size: usize, box_rows: usize, box_cols: usize) -> Array2<usize> {
+    // there are `box_rows` stacks of boxes
+    Array2::from_shape_fn((size, size), |(i, j)| {
+        (i / box_rows) * box_rows + j / box_cols
+    })
+}
+
+/// panics unless `regions` splits the board in as many connected regions as its rows,
+/// each one made of as many cells as the rows of the board
+fn check_regions(regions: &Array2<usize>) {
+    let size = regions.nrows();
+    let mut region_sizes = vec![0; size];
+    for &region in regions.iter() {
+        if region >= size {
+            panic!(
+                "a board with {} rows cannot have more than {} regions",
+                size, size
+            );
+        }
+        region_sizes[region] += 1;
+    }
+    for (region, region_size) in region_sizes.into_iter().enumerate() {
+        if region_size != size {
+            panic!(
+                "region {} has {} cells instead of {}",
+                region, region_size, size
+            );
+        }
+        // visit the region starting from its first cell
+        let first = regions
+            .indexed_iter()
+            .find(|(_, &r)| r == region)
+            .unwrap()
+            .0;
+        let mut visited = vec![first];
+        let mut to_visit = vec![first];
+        while let Some((i, j)) = to_visit.pop() {
+            let neighbours = [
+                (i.wrapping_sub(1), j),
+                (i + 1, j),
+                (i, j.wrapping_sub(1)),
+                (i, j + 1),
+            ];
+            for pos in neighbours {
+                if regions.get(pos) == Some(&region) && !visited.contains(&pos) {
+                    visited.push(pos);
+                    to_visit.push(pos);
+                }
+            }
+        }
+        if visited.len() != size {
+            panic!("region {} is not connected", region);
+        }
+    }
+}
+
+/// regions written one line per row and one character per cell, the regions are numbered
+/// following the order in which their characters first appear
+fn parse_regions(lines: Vec<&str>, size: usize) -> Array2<usize> {
+    if lines.len() != size {
+        panic!("the regions have {} rows instead of {}", lines.len(), size);
+    }
+    let mut labels: Vec<char> = Vec::new();
+    let mut regions = Array2::from_elem((size, size), 0);
+    for (row, line) in lines.iter().enumerate() {
+        let line: Vec<char> = line.trim().chars().collect();
+        if line.len() != size {
+            panic!(
+                "row {} of the regions has {} cells instead of {}",
+                row + 1,
+                line.len(),
+                size
+            );
+        }
+        for (col, c) in line.into_iter().enumerate() {
+            regions[[row, col]] = match labels.iter().position(|&l| l == c) {
+                Some(region) => region,
+                None => {
+                    labels.push(c);
+                    labels.len() - 1
+                }
+            };
+        }
+    }
+    regions
+}
+
+/// parse a box shape written as `RxC`, e.g. `3x2`
+fn parse_box_shape(shape: &str) -> (usize, usize) {
+    try_parse_box_shape(shape).unwrap_or_else(|| panic!("{} is not a valid box shape", shape))
+}
+
+/// same as `parse_box_shape`, but `None` when the shape is not valid
+fn try_parse_box_shape(shape: &str) -> Option<(usize, usize)> {
+    shape
+        .split_once('x')
+        .and_then(|(rows, cols)| Some((rows.parse().ok()?, cols.parse().ok()?)))
 }
 
 #[cfg(test)]
@@ -147,15 +1291,15 @@ mod tests {
         let valid_board = init_valid_board();
         let empty_board = Board::empty();
         for i in 0..9 {
-            assert!(valid_board.check_row(i));
-            assert!(empty_board.check_row(i));
+            assert!(Unit::new("row", valid_board.row_cells(i)).check(&valid_board.values));
+            assert!(Unit::new("row", empty_board.row_cells(i)).check(&empty_board.values));
         }
 
         // two 1s in the first row
         let invalid_board = Board::from_board_dir("tests/two_ones_in_a_row.txt");
-        assert!(!invalid_board.check_row(0));
+        assert!(!Unit::new("row", invalid_board.row_cells(0)).check(&invalid_board.values));
         for i in 1..9 {
-            assert!(valid_board.check_row(i));
+            assert!(Unit::new("row", valid_board.row_cells(i)).check(&valid_board.values));
         }
     }
 
@@ -164,15 +1308,15 @@ mod tests {
         let valid_board = init_valid_board();
         let empty_board = Board::empty();
         for i in 0..9 {
-            assert!(valid_board.check_column(i));
-            assert!(empty_board.check_column(i));
+            assert!(Unit::new("column", valid_board.column_cells(i)).check(&valid_board.values));
+            assert!(Unit::new("column", empty_board.column_cells(i)).check(&empty_board.values));
         }
 
         // two 1s in the first column
         let invalid_board = Board::from_board_dir("tests/two_ones_in_a_col.txt");
-        assert!(!invalid_board.check_column(0));
+        assert!(!Unit::new("column", invalid_board.column_cells(0)).check(&invalid_board.values));
         for i in 1..9 {
-            assert!(valid_board.check_column(i));
+            assert!(Unit::new("column", valid_board.column_cells(i)).check(&valid_board.values));
         }
     }
 
@@ -182,17 +1326,20 @@ mod tests {
         let empty_board = Board::empty();
         for i in 0..3 {
             for j in 0..3 {
-                assert!(valid_board.check_square(i, j));
-                assert!(empty_board.check_square(i, j));
+                assert!(Unit::new("region", valid_board.region_cells(i * 3 + j))
+                    .check(&valid_board.values));
+                assert!(Unit::new("region", empty_board.region_cells(i * 3 + j))
+                    .check(&empty_board.values));
             }
         }
 
         // two 1s in the first square
         let invalid_board = Board::from_board_dir("tests/two_ones_in_a_square.txt");
-        assert!(!invalid_board.check_square(0, 0));
+        assert!(!Unit::new("region", invalid_board.region_cells(0)).check(&invalid_board.values));
         for i in 1..3 {
             for j in 1..3 {
-                assert!(valid_board.check_square(i, j));
+                assert!(Unit::new("region", valid_board.region_cells(i * 3 + j))
+                    .check(&valid_board.values));
             }
         }
     }
@@ -216,4 +1363,489 @@ mod tests {
         let invalid_board = Board::from_board_dir("tests/two_ones_in_a_square.txt");
         assert!(!invalid_board.check_complete());
     }
+
+    #[test]
+    fn test_other_sizes() {
+        let empty_board = Board::empty_with_boxes(4, 4);
+        assert_eq!(empty_board.size(), 16);
+        assert!(empty_board.check_complete());
+
+        for (file, size) in [
+            ("04x04", 4),
+            ("06x06", 6),
+            ("06x06-3x2", 6),
+            ("08x08", 8),
+            ("12x12", 12),
+            ("16x16", 16),
+            ("25x25", 25),
+        ] {
+            let board = Board::from_board_dir(format!("sizes/solution-{}.txt", file).as_str());
+            assert_eq!(board.size(), size);
+            assert!(board.is_complete());
+            assert!(board.check_complete());
+        }
+
+        // letters are numbers beyond 9
+        let board = Board::from_board_dir("sizes/solution-16x16.txt");
+        assert!(board.values.iter().any(|&x| x == 16));
+    }
+
+    #[test]
+    fn test_box_shapes() {
+        assert_eq!(box_shape_of(4), (2, 2));
+        assert_eq!(box_shape_of(6), (2, 3));
+        assert_eq!(box_shape_of(8), (2, 4));
+        assert_eq!(box_shape_of(9), (3, 3));
+        assert_eq!(box_shape_of(12), (3, 4));
+        assert_eq!(box_shape_of(16), (4, 4));
+
+        let board = Board::from_board_dir("sizes/solution-06x06-3x2.txt");
+        assert_eq!(board.regions, box_regions(6, 3, 2));
+        assert!(board.check_complete());
+
+        // the same grid does not fit the guessed 2x3 boxes
+        let board = Board::new(board.values);
+        assert_eq!(board.regions, box_regions(6, 2, 3));
+        assert!(!board.check_complete());
+    }
+
+    #[test]
+    fn test_from_file_with_alphabet() {
+        let board = Board::from_board_dir("sizes/solution-16x16.txt");
+        let hex_board = Board::from_file_with_alphabet(
+            utils::get_board_dir()
+                .unwrap()
+                .join("sizes/solution-16x16-hex.txt")
+                .to_str()
+                .unwrap(),
+            Alphabet::hex(),
+        );
+        assert_eq!(board.values, hex_board.values);
+    }
+
+    #[test]
+    fn test_check_diagonals() {
+        let mut board = Board::from_board_dir("variants/solution-diagonal.txt");
+        assert!(board.is_diagonal());
+        assert!(board.check_complete());
+
+        // the valid board repeats numbers on its diagonals
+        let mut valid_board = init_valid_board();
+        assert!(valid_board.check_complete());
+        valid_board.set_diagonal(true);
+        let diagonals = valid_board.diagonal_cells();
+        assert!(diagonals
+            .into_iter()
+            .any(|cells| !Unit::new("diagonal", cells).check(&valid_board.values)));
+        assert!(!valid_board.check_complete());
+
+        board.values[(0, 0)] = 0;
+        board.values[(8, 8)] = 0;
+        assert!(board.check_complete());
+    }
+
+    #[test]
+    fn test_regions() {
+        let board = Board::from_board_dir("variants/solution-jigsaw.txt");
+        assert_ne!(board.regions, box_regions(9, 3, 3));
+        assert_eq!(
+            board.region_cells(0),
+            vec![
+                (0, 0),
+                (0, 1),
+                (0, 2),
+                (1, 0),
+                (1, 1),
+                (1, 2),
+                (2, 0),
+                (2, 2),
+                (3, 0)
+            ]
+        );
+        assert!(board.check_complete());
+
+        // the jigsaw solution breaks the classic boxes
+        let board = Board::new(board.values);
+        assert!(!board.check_complete());
+    }
+
+    #[test]
+    #[should_panic(expected = "region 0 has 10 cells instead of 9")]
+    fn test_regions_of_wrong_size() {
+        let mut regions = box_regions(9, 3, 3);
+        regions[(0, 3)] = 0;
+        Board::new_with_regions(Array2::from_elem((9, 9), 0), regions);
+    }
+
+    #[test]
+    #[should_panic(expected = "region 0 is not connected")]
+    fn test_disconnected_regions() {
+        let mut regions = box_regions(9, 3, 3);
+        regions[(0, 4)] = 0;
+        regions[(0, 0)] = 1;
+        Board::new_with_regions(Array2::from_elem((9, 9), 0), regions);
+    }
+
+    #[test]
+    fn test_parse_cell() {
+        assert_eq!(parse_cell("r1c1"), (0, 0));
+        assert_eq!(parse_cell("R9C3"), (8, 2));
+        assert_eq!(parse_cell("r12c10"), (11, 9));
+    }
+
+    #[test]
+    fn test_cages() {
+        let board = Board::from_board_dir("variants/solution-killer.txt");
+        assert!(!board.cages().is_empty());
+        assert_eq!(board.cages()[0], Cage::new(14, vec![(1, 8), (1, 7)]));
+        assert!(board.check_complete());
+
+        let mut board = Board::from_board_dir("complete/solution-00.txt");
+        board.add_cage(Cage::new(3, vec![(0, 0), (0, 1)]));
+        assert!(!board.check_complete());
+    }
+
+    #[test]
+    fn test_windows() {
+        let board = Board::from_board_dir("variants/solution-hyper.txt");
+        assert!(board.is_hyper());
+        assert!(board.check_complete());
+        let windows = board.window_cells();
+        assert_eq!(windows.len(), 4);
+        assert_eq!(windows[0][0], (1, 1));
+        assert_eq!(windows[3][8], (7, 7));
+        assert_eq!(Board::empty_with_boxes(4, 4).window_cells().len(), 9);
+
+        // the windows of 3x2 boxes are 3 rows tall
+        let windows = Board::empty_with_boxes(3, 2).window_cells();
+        assert_eq!(windows.len(), 1);
+        assert_eq!(windows[0], [(1, 1), (1, 2), (2, 1), (2, 2), (3, 1), (3, 2)]);
+        assert_eq!(Board::empty_with_boxes(2, 3).box_shape(), Some((2, 3)));
+        let jigsaw = Board::from_board_dir("variants/quiz-jigsaw.txt");
+        assert_eq!(jigsaw.box_shape(), None);
+
+        // the valid board repeats numbers in its windows
+        let mut valid_board = init_valid_board();
+        valid_board.set_hyper(true);
+        let windows = valid_board.window_cells();
+        assert!(windows
+            .into_iter()
+            .any(|cells| !Unit::new("window", cells).check(&valid_board.values)));
+        assert!(!valid_board.check_complete());
+    }
+
+    #[test]
+    #[should_panic(expected = "need a board made of boxes")]
+    fn test_hyper_jigsaw() {
+        Board::from_board_dir("variants/quiz-jigsaw.txt").set_hyper(true);
+    }
+
+    #[test]
+    fn test_constraints() {
+        fn is_send_and_sync<T: Send + Sync>() {}
+        is_send_and_sync::<Board>();
+        let mut board = init_valid_board();
+        assert_eq!(board.constraints().len(), 27);
+        board.set_diagonal(true);
+        assert_eq!(board.constraints().len(), 29);
+        assert_eq!(
+            board.constraints()[28].explain(),
+            Some("anti-diagonal cannot repeat a number".to_string())
+        );
+        board.set_diagonal(false);
+
+        // the valid board breaks a custom rule
+        assert!(board.check_complete());
+        board.add_constraint(Unit::new("corners", vec![(0, 0), (0, 8), (8, 0), (8, 8)]));
+        assert_eq!(board.custom_constraints().len(), 1);
+        assert_eq!(board.constraints().len(), 28);
+        // two corners hold 9
+        assert!(!board.check_complete());
+    }
+
+    #[test]
+    fn test_latin_square() {
+        let mut board = Board::empty_latin_square(7);
+        assert!(board.is_latin_square());
+        assert_eq!(board.units().len(), 14);
+
+        // swapping two columns of different boxes keeps a Latin square, but breaks the boxes
+        let mut valid_board = init_valid_board();
+        for i in 0..9 {
+            valid_board.values.swap((i, 0), (i, 3));
+        }
+        assert!(!valid_board.check_complete());
+        valid_board.set_latin_square(true);
+        assert!(valid_board.check_complete());
+
+        board.values[(0, 0)] = 1;
+        board.values[(6, 0)] = 1;
+        assert!(!board.check_complete());
+    }
+
+    #[test]
+    fn test_grid_text() {
+        let text = std::fs::read_to_string("boards/complete/quiz-00.txt").unwrap();
+        assert_eq!(
+            Board::from_board_dir("complete/quiz-00.txt").grid_text(),
+            text
+        );
+
+        let mut board = Board::empty_with_boxes(4, 4);
+        board.set_alphabet(Alphabet::hex());
+        board.values[(0, 0)] = 1;
+        assert!(board.grid_text().starts_with("0...............\n"));
+    }
+
+    #[test]
+    fn test_units() {
+        let mut board = Board::empty();
+        assert_eq!(board.units().len(), 27);
+        board.set_diagonal(true);
+        board.set_hyper(true);
+        board.add_cage(Cage::new(3, vec![(0, 0), (0, 1)]));
+        assert_eq!(board.units().len(), 27 + 2 + 4 + 1);
+        // the center cell is on both diagonals and in no window
+        let units = board.units();
+        assert_eq!(
+            units.iter().filter(|unit| unit.contains(&(4, 4))).count(),
+            5
+        );
+        assert_eq!(
+            units.iter().filter(|unit| unit.contains(&(1, 1))).count(),
+            5
+        );
+    }
+
+    #[test]
+    fn test_chess_moves() {
+        let mut board = Board::empty();
+        assert!(board.chess_peers(4, 4).is_empty());
+        board.set_anti_knight(true);
+        assert_eq!(board.chess_peers(4, 4).len(), 8);
+        assert_eq!(board.chess_peers(0, 0), vec![(1, 2), (2, 1)]);
+        board.set_anti_king(true);
+        assert_eq!(board.chess_peers(0, 0).len(), 5);
+
+        for name in ["anti-knight", "anti-king"] {
+            let board = Board::from_board_dir(format!("variants/solution-{}.txt", name).as_str());
+            assert!(board.is_anti_knight() || board.is_anti_king());
+            assert!(board.check_complete());
+        }
+
+        // 1 and 1 a knight's move apart
+        let mut board = Board::empty();
+        board.values[(0, 2)] = 1;
+        board.values[(1, 4)] = 1;
+        assert!(board.check_complete());
+        board.set_anti_knight(true);
+        assert!(!board.check_complete());
+        let broken: Vec<String> = board
+            .constraints()
+            .iter()
+            .filter(|constraint| !constraint.check(&board.values))
+            .filter_map(|constraint| constraint.explain())
+            .collect();
+        assert_eq!(
+            broken,
+            ["cells a knight's move apart cannot hold the same number"]
+        );
+    }
+
+    #[test]
+    fn test_non_consecutive() {
+        let mut board = Board::empty_with_boxes(2, 2);
+        board.set_non_consecutive(true);
+        // 4 rows and 4 columns of 3 pairs each
+        assert_eq!(board.relations().len(), 24);
+
+        let board = Board::from_board_dir("variants/solution-non-consecutive.txt");
+        assert!(board.is_non_consecutive());
+        assert!(board.check_complete());
+
+        let mut board = init_valid_board();
+        assert!(board.check_complete());
+        board.set_non_consecutive(true);
+        assert!(!board.check_complete());
+    }
+
+    #[test]
+    fn test_dots() {
+        let mut board = Board::from_board_dir("variants/solution-kropki.txt");
+        assert!(board.is_negative_kropki());
+        assert_eq!(board.dots()[0], (((0, 0), (0, 1)), Dot::White));
+        assert!(board.check_complete());
+
+        // without its dot the pair is consecutive, which the negative rule forbids
+        board.dots.remove(0);
+        assert!(!board.check_complete());
+        board.set_negative_kropki(false);
+        assert!(board.check_complete());
+
+        // the cells are sorted
+        let mut board = Board::empty();
+        board.add_dot(((1, 0), (0, 0)), Dot::Black);
+        assert_eq!(board.dot_of(((0, 0), (1, 0))), Some(Dot::Black));
+        assert_eq!(parse_edge("r2c1 r1c1"), ((1, 0), (0, 0)));
+    }
+
+    #[test]
+    #[should_panic(expected = "cells (0, 0) and (1, 1) are not adjacent")]
+    fn test_dot_between_far_cells() {
+        Board::empty().add_dot(((0, 0), (1, 1)), Dot::White);
+    }
+
+    #[test]
+    fn test_extra_regions() {
+        let mut board = Board::from_board_dir("variants/solution-diagonal.txt");
+        board.set_diagonal(false);
+        board.add_extra_region(board.diagonal_cells()[0].clone());
+        assert!(board.check_complete());
+        assert_eq!(board.units().len(), 28);
+
+        // the first row without its last cell and a cell of the second row
+        let mut cells = board.row_cells(0);
+        cells[8] = (1, 0);
+        board.add_extra_region(cells);
+        assert!(!board.check_complete());
+    }
+
+    #[test]
+    #[should_panic(expected = "a region must have 9 cells instead of 2")]
+    fn test_short_extra_region() {
+        Board::empty().add_extra_region(vec![(0, 0), (1, 1)]);
+    }
+
+    #[test]
+    fn test_xvs() {
+        let mut board = Board::from_board_dir("variants/solution-xv.txt");
+        assert!(board.is_negative_xv());
+        assert_eq!(board.xvs()[0], (((0, 1), (0, 2)), Xv::V));
+        assert!(board.check_complete());
+        assert_eq!(board.sums().len(), board.xvs().len());
+
+        // without its marker the pair adds up to 5, which the negative rule forbids
+        board.xvs.remove(0);
+        assert!(!board.check_complete());
+        board.set_negative_xv(false);
+        assert!(board.check_complete());
+
+        board.add_xv(((0, 2), (0, 1)), Xv::X);
+        assert_eq!(board.xv_of(((0, 1), (0, 2))), Some(Xv::X));
+        assert!(!board.check_complete());
+    }
+
+    #[test]
+    fn test_little_killers() {
+        let mut board = Board::from_board_dir("variants/solution-little-killer.txt");
+        assert_eq!(
+            board.little_killers()[0],
+            LittleKiller::new(24, (5, 8), Direction::UpLeft)
+        );
+        assert!(board.check_complete());
+        assert_eq!(
+            parse_little_killer("9 r1c2 down-left"),
+            LittleKiller::new(9, (0, 1), Direction::DownLeft)
+        );
+
+        board.add_little_killer(LittleKiller::new(10, (0, 7), Direction::DownRight));
+        assert!(!board.check_complete());
+    }
+
+    #[test]
+    #[should_panic(expected = "the diagonal of the little killer does not start at the edge")]
+    fn test_little_killer_inside_the_grid() {
+        Board::empty().add_little_killer(LittleKiller::new(10, (1, 1), Direction::DownRight));
+    }
+
+    #[test]
+    fn test_thermos() {
+        let mut board = Board::from_board_dir("variants/solution-thermo.txt");
+        assert_eq!(board.thermos().len(), 9);
+        assert_eq!(
+            board.thermos()[0],
+            Thermo::new(vec![(0, 5), (1, 6), (2, 7), (2, 8)])
+        );
+        assert!(board.check_complete());
+
+        // the cells of the bulb and of the tip swapped
+        let bulb = board.values[(0, 5)];
+        board.values[(0, 5)] = board.values[(2, 8)];
+        board.values[(2, 8)] = bulb;
+        assert!(!board.check_complete());
+    }
+
+    #[test]
+    fn test_sandwiches() {
+        let mut board = Board::from_board_dir("variants/solution-sandwich.txt");
+        assert_eq!(board.row_sandwiches()[1], Some(5));
+        assert_eq!(board.column_sandwiches()[8], Some(12));
+        assert!(board.check_complete());
+
+        board.set_row_sandwich(1, Some(6));
+        assert!(!board.check_complete());
+        board.set_row_sandwich(1, None);
+        assert!(board.check_complete());
+
+        assert_eq!(
+            parse_sandwiches("0 . 12 35", 4),
+            vec![Some(0), None, Some(12), Some(35)]
+        );
+    }
+
+    #[test]
+    fn test_arrows() {
+        let mut board = Board::from_board_dir("variants/solution-arrow.txt");
+        assert_eq!(board.arrows().len(), 8);
+        assert_eq!(board.arrows()[0], Arrow::new((3, 1), vec![(4, 1), (3, 2)]));
+        assert!(board.check_complete());
+
+        board.values[(3, 1)] = 0;
+        assert!(board.check_complete());
+        board.values[(4, 1)] = 0;
+        board.values[(3, 2)] = 0;
+        assert!(board.check_complete());
+
+        let mut board = Board::empty();
+        board.add_arrow(parse_arrow("r1c1 r2c2"));
+        board.values[(0, 0)] = 3;
+        board.values[(1, 1)] = 4;
+        assert!(!board.check_complete());
+    }
+
+    #[test]
+    fn test_parities() {
+        let mut board = Board::from_board_dir("variants/solution-even-odd.txt");
+        assert_eq!(board.parity_of(0, 1), Some(Parity::Even));
+        assert_eq!(board.parity_of(0, 0), Some(Parity::Odd));
+        assert_eq!(board.parity_of(0, 2), None);
+        assert!(board.check_complete());
+
+        board.set_parity(0, 1, Some(Parity::Odd));
+        assert!(!board.check_complete());
+    }
+
+    #[test]
+    fn test_inequalities() {
+        let mut board = Board::empty();
+        let (edge, ordering) = parse_inequality("r1c2 > r1c1");
+        board.add_inequality(edge, ordering);
+        assert_eq!(board.inequality_of(((0, 0), (0, 1))), Some(Ordering::Less));
+        assert_eq!(board.relations(), vec![((0, 0), (0, 1), Relation::Less)]);
+        board.values[(0, 0)] = 5;
+        board.values[(0, 1)] = 4;
+        assert!(!board.check_complete());
+
+        let board = Board::from_board_dir("variants/solution-greater-than.txt");
+        assert_eq!(board.inequalities().len(), 108);
+        assert!(board.check_complete());
+    }
+
+    #[test]
+    #[should_panic(expected = "cell (0, 1) is already in a cage")]
+    fn test_overlapping_cages() {
+        let mut board = Board::empty();
+        board.add_cage(Cage::new(3, vec![(0, 0), (0, 1)]));
+        board.add_cage(Cage::new(3, vec![(0, 1), (0, 2)]));
+    }
 }
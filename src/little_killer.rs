@@ -0,0 +1,102 @@
+/// the direction a little killer clue points to, from outside the grid along a diagonal
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Direction {
+    DownRight,
+    DownLeft,
+    UpRight,
+    UpLeft,
+}
+
+impl Direction {
+    /// the change of row and column moving one cell in this direction
+    pub(crate) fn step(&self) -> (isize, isize) {
+        match self {
+            Direction::DownRight => (1, 1),
+            Direction::DownLeft => (1, -1),
+            Direction::UpRight => (-1, 1),
+            Direction::UpLeft => (-1, -1),
+        }
+    }
+
+    /// the arrow drawn for the direction
+    pub(crate) fn arrow(&self) -> char {
+        match self {
+            Direction::DownRight => '↘',
+            Direction::DownLeft => '↙',
+            Direction::UpRight => '↗',
+            Direction::UpLeft => '↖',
+        }
+    }
+}
+
+/// a little killer clue, the numbers along the diagonal starting from `start` add up to
+/// `sum`. Numbers can repeat along the diagonal, unless they share a unit
+#[derive(Clone, Debug, PartialEq)]
+pub struct LittleKiller {
+    sum: u32,
+    start: (usize, usize),
+    direction: Direction,
+}
+
+impl LittleKiller {
+    /// a diagonal starting from `start`, the first cell in the grid the clue points to
+    pub fn new(sum: u32, start: (usize, usize), direction: Direction) -> Self {
+        LittleKiller {
+            sum,
+            start,
+            direction,
+        }
+    }
+
+    pub fn sum(&self) -> u32 {
+        self.sum
+    }
+
+    pub fn start(&self) -> (usize, usize) {
+        self.start
+    }
+
+    pub fn direction(&self) -> Direction {
+        self.direction
+    }
+
+    /// the cells of the diagonal on a board with `size` rows, from `start` to the edge
+    pub fn cells(&self, size: usize) -> Vec<(usize, usize)> {
+        let (di, dj) = self.direction.step();
+        let size = size as isize;
+        let mut cells = Vec::new();
+        let (mut i, mut j) = (self.start.0 as isize, self.start.1 as isize);
+        while (0..size).contains(&i) && (0..size).contains(&j) {
+            cells.push((i as usize, j as usize));
+            i += di;
+            j += dj;
+        }
+        cells
+    }
+
+    /// whether the clue can be written outside a board with `size` rows, that is the cell
+    /// before `start` along the diagonal is outside the grid
+    pub(crate) fn starts_at_edge(&self, size: usize) -> bool {
+        let (di, dj) = self.direction.step();
+        let (i, j) = (self.start.0 as isize - di, self.start.1 as isize - dj);
+        let size = size as isize;
+        !(0..size).contains(&i) || !(0..size).contains(&j)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cells() {
+        let little_killer = LittleKiller::new(15, (0, 6), Direction::DownRight);
+        assert_eq!(little_killer.cells(9), [(0, 6), (1, 7), (2, 8)]);
+        assert!(little_killer.starts_at_edge(9));
+        let little_killer = LittleKiller::new(15, (8, 3), Direction::UpLeft);
+        assert_eq!(little_killer.cells(9), [(8, 3), (7, 2), (6, 1), (5, 0)]);
+        assert!(little_killer.starts_at_edge(9));
+        let little_killer = LittleKiller::new(15, (1, 3), Direction::DownLeft);
+        assert!(!little_killer.starts_at_edge(9));
+    }
+}
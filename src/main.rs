@@ -1,39 +1,117 @@
-use std::path::PathBuf;
+use std::{
+    collections::HashSet,
+    fs,
+    path::{Path, PathBuf},
+    process,
+    time::{Duration, Instant},
+};
 
 use clap::{Parser, Subcommand};
 
-use sudoku::Board;
+use sudoku::{Alphabet, Board, Difficulty, MultiGrid, Seed, Symmetry};
 
-/// Simple program to greet a person
+/// Solve, show and generate sudoku puzzles, along with many of their variants
 #[derive(Parser)]
 #[clap(author, version, about, long_about = None)]
 #[clap(propagate_version = true)]
 struct Cli {
     #[clap(subcommand)]
     command: Commands,
+
+    /// Symbols of the numbers, either `digits`, `hex` or the list of symbols (e.g. `WORDPLAYS`)
+    #[clap(long, global = true, value_name = "SYMBOLS")]
+    alphabet: Option<Alphabet>,
+
+    /// The file holds several overlapping grids, such as a Samurai sudoku. Files placing grids
+    /// with `layout` or `grid` are read as such anyway, only `solve` and `show` support them
+    #[clap(long, global = true)]
+    multi_grid: bool,
 }
 
 #[derive(Subcommand)]
 enum Commands {
+    /// Solve a puzzle and print the numbers found
     Solve {
         /// Path to the file containing the board
         #[clap(parse(from_os_str), value_name = "FILE")]
         path: PathBuf,
     },
+    /// Print a puzzle with its rules
     Show {
         /// Path to the file containing the board
         #[clap(parse(from_os_str), value_name = "FILE")]
         path: PathBuf,
     },
+    /// Print the symmetries of the pattern of the givens
+    Symmetries {
+        /// Path to the file containing the board
+        #[clap(parse(from_os_str), value_name = "FILE")]
+        path: PathBuf,
+    },
+    /// Generate puzzles with a unique solution, written as `quiz-NN.txt` and `solution-NN.txt`
+    Generate {
+        /// Number of puzzles to generate
+        #[clap(short = 'n', long, default_value = "1")]
+        count: usize,
+        /// Directory the puzzles are written to
+        #[clap(short, long, parse(from_os_str), default_value = "generated")]
+        output: PathBuf,
+        /// Board file whose rules and givens the puzzles follow, an empty 9x9 sudoku by default
+        #[clap(long, parse(from_os_str), value_name = "FILE")]
+        template: Option<PathBuf>,
+        /// Number or text the puzzles are generated from, the same seed always gives the same
+        /// puzzles. A random one is drawn by default, and the seed of each puzzle is written in
+        /// its files
+        #[clap(long)]
+        seed: Option<Seed>,
+        /// Difficulty of the puzzles: `easy`, `medium`, `hard` or `expert`
+        #[clap(long)]
+        difficulty: Option<Difficulty>,
+        /// Symmetry of the givens: `rotational`, `horizontal`, `vertical`, `diagonal`,
+        /// `anti-diagonal` or `dihedral`
+        #[clap(long)]
+        symmetry: Option<Symmetry>,
+        /// Board file whose cells holding a number are the givens of the puzzles, e.g. a shape
+        /// drawn with `1`s
+        #[clap(long, parse(from_os_str), value_name = "FILE", conflicts_with_all = &["difficulty", "symmetry"])]
+        mask: Option<PathBuf>,
+        /// Seconds the puzzles of a difficulty or of a mask can take to be found, a minute by
+        /// default
+        #[clap(long, value_name = "SECONDS")]
+        time_limit: Option<u64>,
+    },
 }
 
+/// the time puzzles fitting a mask can take without `--time-limit`, a mask may fit none
+const MASK_TIME_LIMIT: Duration = Duration::from_secs(60);
+
+/// the time puzzles of a difficulty can take without `--time-limit`, a template may have
+/// none that hard
+const DIFFICULTY_TIME_LIMIT: Duration = Duration::from_secs(60);
+
 fn main() {
     let cli = Cli::parse();
+    let alphabet = cli.alphabet.unwrap_or_default();
+    let multi_grid = |path: &Path| cli.multi_grid || places_grids(path);
 
     match cli.command {
+        Commands::Solve { path } if multi_grid(&path) => {
+            println!("{}", path.as_path().as_os_str().to_str().unwrap());
+            let multi_grid = load_multi_grid(&path, alphabet);
+            match multi_grid.solve() {
+                Ok(solved) => {
+                    println!("Board solved:");
+                    solved.print_diff(&multi_grid);
+                }
+                Err(_) => {
+                    println!("Board is not solvable:");
+                    multi_grid.print_complete();
+                }
+            }
+        }
         Commands::Solve { path } => {
             println!("{}", path.as_path().as_os_str().to_str().unwrap());
-            let board = Board::from_file(path.as_path().as_os_str().to_str().unwrap());
+            let board = load_board(&path, alphabet);
             match board.solve() {
                 Ok(solved) => {
                     println!("Board solved:");
@@ -45,9 +123,172 @@ fn main() {
                 }
             }
         }
+        Commands::Show { path } if multi_grid(&path) => {
+            load_multi_grid(&path, alphabet).print_complete();
+        }
         Commands::Show { path } => {
-            let board = Board::from_file(path.as_path().as_os_str().to_str().unwrap());
+            let board = load_board(&path, alphabet);
             board.print_complete();
         }
+        Commands::Symmetries { path } => {
+            refuse_multi_grid(multi_grid(&path), "symmetries");
+            let symmetries = load_board(&path, alphabet).symmetries();
+            match symmetries.is_empty() {
+                true => println!("The givens have no symmetry"),
+                false => {
+                    for symmetry in symmetries {
+                        println!("{}", symmetry);
+                    }
+                }
+            }
+        }
+        Commands::Generate {
+            count,
+            output,
+            template,
+            seed,
+            difficulty,
+            symmetry,
+            mask,
+            time_limit,
+        } => {
+            let mut files = template.iter().chain(mask.iter());
+            refuse_multi_grid(
+                cli.multi_grid || files.any(|path| places_grids(path)),
+                "generate",
+            );
+            let (board, rules) = match &template {
+                Some(path) => {
+                    let rules = rules_of(path).unwrap_or_else(|message| {
+                        println!("{}", message);
+                        process::exit(1);
+                    });
+                    (load_board(path, alphabet.clone()), rules)
+                }
+                None => (Board::empty(), String::new()),
+            };
+            let mask = mask.map(|path| load_board(&path, alphabet));
+            let budget = match (time_limit, &mask, difficulty) {
+                (Some(seconds), _, _) => Duration::from_secs(seconds),
+                (None, Some(_), _) => MASK_TIME_LIMIT,
+                (None, None, Some(_)) => DIFFICULTY_TIME_LIMIT,
+                // the other puzzles are always found
+                (None, None, None) => Duration::MAX,
+            };
+            let start = Instant::now();
+            // each puzzle has its own seed, so that it can be generated again alone
+            let mut next_seed = seed.unwrap_or_else(Seed::random);
+            let mut grids = HashSet::new();
+            fs::create_dir_all(&output).unwrap();
+            println!("generating...");
+            while grids.len() < count {
+                let left = budget.saturating_sub(start.elapsed());
+                let found = match (&mask, difficulty, symmetry) {
+                    (Some(mask), _, _) => board
+                        .generate_with_mask(mask, next_seed, left)
+                        .map_err(|error| format!("No puzzle fits the mask, {}", error)),
+                    (None, Some(difficulty), _) => board
+                        .generate_with_difficulty(difficulty, symmetry, next_seed, left)
+                        .ok_or_else(|| "Time is up".to_string()),
+                    (None, None, Some(symmetry)) => {
+                        Ok((board.generate_symmetric(symmetry, next_seed), next_seed))
+                    }
+                    (None, None, None) => Ok((board.generate_with_seed(next_seed), next_seed)),
+                };
+                let (quiz, seed) = match found {
+                    Ok(found) => found,
+                    Err(message) => {
+                        println!("{}, generated {} of {} games.", message, grids.len(), count);
+                        process::exit(1);
+                    }
+                };
+                next_seed = seed.offset(1);
+                if !grids.insert(quiz.grid_text()) {
+                    continue;
+                }
+                let solution = quiz.solve().unwrap();
+                let mut metadata = format!("# seed {}\n", seed);
+                if let Some(difficulty) = difficulty {
+                    metadata += &format!("# difficulty {}\n", difficulty);
+                }
+                if let Some(symmetry) = symmetry {
+                    metadata += &format!("# symmetry {}\n", symmetry);
+                }
+                if let Some(mask) = &mask {
+                    metadata += &format!("# mask {}\n", mask.givens_pattern());
+                }
+                let write = |name: String, board: &Board| {
+                    let rules = match rules.is_empty() {
+                        true => "\n",
+                        false => &rules,
+                    };
+                    let text = format!("{}{}{}", board.grid_text(), rules, metadata);
+                    fs::write(output.join(name), text).unwrap()
+                };
+                let i = grids.len() - 1;
+                write(format!("quiz-{:02}.txt", i), &quiz);
+                write(format!("solution-{:02}.txt", i), &solution);
+            }
+            println!("Done, generated {} games.", count);
+        }
+    }
+}
+
+/// the board of the file, or exit with the reason a puzzle description is not valid
+fn load_board(path: &Path, alphabet: Alphabet) -> Board {
+    Board::try_from_file_with_alphabet(path.as_os_str().to_str().unwrap(), alphabet).unwrap_or_else(
+        |error| {
+            println!("Cannot read {}, {}.", path.display(), error);
+            process::exit(1);
+        },
+    )
+}
+
+fn load_multi_grid(path: &Path, alphabet: Alphabet) -> MultiGrid {
+    MultiGrid::from_file_with_alphabet(path.as_os_str().to_str().unwrap(), alphabet)
+}
+
+/// whether the directives of the file place several grids (`layout` or `grid`), so that it
+/// is read as a multi-grid even without `--multi-grid`
+fn places_grids(path: &Path) -> bool {
+    if let Some("toml" | "json") = path.extension().and_then(|extension| extension.to_str()) {
+        return false;
+    }
+    let contents = fs::read_to_string(path).unwrap_or_default();
+    contents
+        .lines()
+        .skip_while(|line| !line.trim().is_empty())
+        .any(|line| matches!(line.split_whitespace().next(), Some("layout" | "grid")))
+}
+
+/// exit with a message when a command that only reads single boards is given a multi-grid
+fn refuse_multi_grid(multi_grid: bool, command: &str) {
+    if multi_grid {
+        println!(
+            "The {} command does not support multi-grid puzzles.",
+            command
+        );
+        process::exit(1);
+    }
+}
+
+/// the directives following the grid of a board file, with the empty line before them,
+/// so that generated files keep the rules of their template. Puzzle descriptions (TOML or
+/// JSON) cannot be templates, the seed of the puzzles would not fit in them
+fn rules_of(path: &Path) -> Result<String, String> {
+    if let Some("toml" | "json") = path.extension().and_then(|extension| extension.to_str()) {
+        return Err(format!(
+            "The template must be a board file, {} is a puzzle description.",
+            path.display()
+        ));
+    }
+    let contents = fs::read_to_string(path).unwrap();
+    let rules: Vec<&str> = contents
+        .lines()
+        .skip_while(|line| !line.trim().is_empty())
+        .collect();
+    match rules.iter().all(|line| line.trim().is_empty()) {
+        true => Ok(String::new()),
+        false => Ok(rules.join("\n") + "\n"),
     }
 }
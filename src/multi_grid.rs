@@ -0,0 +1,341 @@
+use ndarray::prelude::*;
+use std::{fs::File, io::Read};
+
+use crate::{box_shape_of, parse_cell, utils, Alphabet, Board};
+
+/// how the grids of the classic multi-grid puzzles overlap, always by whole boxes
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Layout {
+    /// two grids sharing a corner box
+    Twodoku,
+    /// four grids sharing their corner boxes with a central one
+    Samurai,
+    /// four grids in a square, each one shifted by a box from the others
+    Butterfly,
+    /// a central grid and four grids shifted by a box above, below and at its sides
+    Flower,
+}
+
+impl Layout {
+    /// position on the canvas of the top left cell of each grid, for grids of `size` rows
+    /// with the boxes guessed from their size
+    pub fn offsets(&self, size: usize) -> Vec<(usize, usize)> {
+        let (box_rows, box_cols) = box_shape_of(size);
+        match self {
+            Layout::Twodoku => vec![(0, 0), (size - box_rows, size - box_cols)],
+            Layout::Samurai => vec![
+                (0, 0),
+                (0, size + box_cols),
+                (size - box_rows, size - box_cols),
+                (size + box_rows, 0),
+                (size + box_rows, size + box_cols),
+            ],
+            Layout::Butterfly => vec![(0, 0), (0, box_cols), (box_rows, 0), (box_rows, box_cols)],
+            Layout::Flower => vec![
+                (0, box_cols),
+                (box_rows, 0),
+                (box_rows, box_cols),
+                (box_rows, 2 * box_cols),
+                (2 * box_rows, box_cols),
+            ],
+        }
+    }
+}
+
+/// a puzzle made of grids of the same size overlapping on a larger canvas, such as a Samurai
+/// sudoku. Each grid is a `Board` following its own rules, the cells shared by more grids
+/// hold the same number in all of them
+#[derive(Clone, Default)]
+pub struct MultiGrid {
+    pub(crate) grids: Vec<Board>,
+    /// position on the canvas of the top left cell of each grid
+    offsets: Vec<(usize, usize)>,
+}
+
+impl MultiGrid {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// empty grids of `size` rows placed as in `layout`
+    pub fn with_layout(layout: Layout, size: usize) -> Self {
+        let mut multi_grid = Self::new();
+        for offset in layout.offsets(size) {
+            multi_grid.add_grid(Board::new(Array2::from_elem((size, size), 0)), offset);
+        }
+        multi_grid
+    }
+
+    /// the canvas is read one line per row and one character per cell as in
+    /// `Board::from_file`, the characters of the cells outside every grid are ignored.
+    ///
+    /// The canvas is followed, after an empty line, by the grids:
+    /// - `layout NAME`: the grids are placed as in one of the layouts, `twodoku`, `samurai`,
+    ///   `butterfly` or `flower`, their size is guessed from the rows of the canvas
+    /// - `grid SIZE CELL`: a grid of SIZE rows whose top left cell is CELL of the canvas,
+    ///   written as `r1c1` (row 1, column 1)
+    pub fn from_file(path: &str) -> Self {
+        Self::from_file_with_alphabet(path, Alphabet::default())
+    }
+
+    /// same as `from_file`, but the numbers are written with the symbols of `alphabet`
+    pub fn from_file_with_alphabet(path: &str, alphabet: Alphabet) -> Self {
+        let mut file = File::open(path).unwrap();
+        let mut contents = String::new();
+        file.read_to_string(&mut contents).unwrap();
+        let mut lines = contents.lines();
+        let canvas_lines: Vec<&str> = lines
+            .by_ref()
+            .take_while(|line| !line.trim().is_empty())
+            .collect();
+        let mut grids = Vec::new();
+        for line in lines {
+            let line = line.trim();
+            let (directive, argument) = line.split_once(' ').unwrap_or((line, ""));
+            match directive {
+                "" => (),
+                "layout" => {
+                    let layout = parse_layout(argument.trim());
+                    let size = (1..=canvas_lines.len())
+                        .find(|&size| {
+                            let offsets = layout.offsets(size);
+                            offsets.iter().map(|offset| offset.0 + size).max()
+                                == Some(canvas_lines.len())
+                        })
+                        .unwrap_or_else(|| panic!("the canvas does not fit a {} layout", argument));
+                    grids.extend(
+                        layout
+                            .offsets(size)
+                            .into_iter()
+                            .map(|offset| (size, offset)),
+                    );
+                }
+                "grid" => grids.push(parse_grid(argument)),
+                _ => panic!("unknown directive: {}", line),
+            }
+        }
+        let mut multi_grid = Self::new();
+        for (size, (row, col)) in grids {
+            let values = Array2::from_shape_fn((size, size), |(i, j)| {
+                canvas_lines
+                    .get(row + i)
+                    .and_then(|line| line.chars().nth(col + j))
+                    .and_then(|c| alphabet.value_of(c))
+                    .unwrap_or(0)
+            });
+            if values.iter().any(|&value| value as usize > size) {
+                panic!(
+                    "the grid in {:?} has numbers higher than {}",
+                    (row, col),
+                    size
+                );
+            }
+            let mut grid = Board::new(values);
+            grid.set_alphabet(alphabet.clone());
+            multi_grid.add_grid(grid, (row, col));
+        }
+        multi_grid
+    }
+
+    pub fn from_board_dir(path: &str) -> Self {
+        let board_path = utils::get_board_dir().unwrap();
+        Self::from_file(board_path.as_path().join(path).to_str().unwrap())
+    }
+
+    pub fn grids(&self) -> &[Board] {
+        &self.grids
+    }
+
+    pub fn offsets(&self) -> &[(usize, usize)] {
+        &self.offsets
+    }
+
+    /// number of rows of each grid
+    pub fn grid_size(&self) -> usize {
+        self.grids.first().map_or(0, |grid| grid.size())
+    }
+
+    /// number of rows and columns of the canvas holding all the grids
+    pub fn canvas_shape(&self) -> (usize, usize) {
+        let size = self.grid_size();
+        let rows = self.offsets.iter().map(|offset| offset.0 + size).max();
+        let cols = self.offsets.iter().map(|offset| offset.1 + size).max();
+        (rows.unwrap_or(0), cols.unwrap_or(0))
+    }
+
+    /// place a grid with its top left cell in `offset` of the canvas. Panics if the grid
+    /// has a different size from the others or if a number of a shared cell differs from
+    /// the one of another grid, the numbers of the shared cells are copied to all the grids
+    pub fn add_grid(&mut self, mut grid: Board, offset: (usize, usize)) {
+        if !self.grids.is_empty() && grid.size() != self.grid_size() {
+            panic!("all the grids must have {} rows", self.grid_size());
+        }
+        for ((i, j), value) in grid.values.indexed_iter_mut() {
+            let pos = (offset.0 + i, offset.1 + j);
+            match self.value(pos.0, pos.1) {
+                Some(other) if other != 0 && *value == 0 => *value = other,
+                Some(other) if other != 0 && *value != other => {
+                    panic!("cell {:?} holds both {} and {}", pos, other, value)
+                }
+                _ => (),
+            }
+        }
+        // the numbers of the new grid go to the cells it shares with the others
+        for ((i, j), &value) in grid.values.indexed_iter() {
+            for (g, pos) in self.views_of(offset.0 + i, offset.1 + j) {
+                if value != 0 {
+                    self.grids[g].values[pos] = value;
+                }
+            }
+        }
+        self.grids.push(grid);
+        self.offsets.push(offset);
+    }
+
+    /// the number of a cell of the canvas, 0 when empty and `None` outside every grid
+    pub fn value(&self, row: usize, col: usize) -> Option<u8> {
+        self.views_of(row, col)
+            .first()
+            .map(|&(g, pos)| self.grids[g].values[pos])
+    }
+
+    /// whether every cell of every grid holds a number
+    pub fn is_complete(&self) -> bool {
+        self.grids.iter().all(|grid| grid.is_complete())
+    }
+
+    /// whether every grid follows its rules
+    pub fn check_complete(&self) -> bool {
+        self.grids.iter().all(|grid| grid.check_complete())
+    }
+
+    /// the grids covering a cell of the canvas, with the position of the cell in each grid
+    pub(crate) fn views_of(&self, row: usize, col: usize) -> Vec<(usize, (usize, usize))> {
+        let size = self.grid_size();
+        self.offsets
+            .iter()
+            .enumerate()
+            .filter(|(_, &(i, j))| (i..i + size).contains(&row) && (j..j + size).contains(&col))
+            .map(|(g, &(i, j))| (g, (row - i, col - j)))
+            .collect()
+    }
+}
+
+/// parse the name of a layout, e.g. `samurai`
+fn parse_layout(layout: &str) -> Layout {
+    match layout {
+        "twodoku" => Layout::Twodoku,
+        "samurai" => Layout::Samurai,
+        "butterfly" => Layout::Butterfly,
+        "flower" => Layout::Flower,
+        _ => panic!("{} is not a known layout", layout),
+    }
+}
+
+/// parse a grid written as its size followed by its top left cell, e.g. `9 r1c13`
+fn parse_grid(grid: &str) -> (usize, (usize, usize)) {
+    match grid.split_whitespace().collect::<Vec<_>>()[..] {
+        [size, cell] => {
+            let size = size
+                .parse()
+                .unwrap_or_else(|_| panic!("{} is not a valid grid size", size));
+            (size, parse_cell(cell))
+        }
+        _ => panic!("{} is not a valid grid", grid),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_layouts() {
+        let shapes = [
+            (Layout::Twodoku, (15, 15)),
+            (Layout::Samurai, (21, 21)),
+            (Layout::Butterfly, (12, 12)),
+            (Layout::Flower, (15, 15)),
+        ];
+        for (layout, shape) in shapes {
+            assert_eq!(MultiGrid::with_layout(layout, 9).canvas_shape(), shape);
+        }
+        // the boxes of a 6x6 grid are 2 rows high and 3 columns wide
+        assert_eq!(Layout::Twodoku.offsets(6), [(0, 0), (4, 3)]);
+
+        let samurai = MultiGrid::with_layout(Layout::Samurai, 9);
+        assert_eq!(samurai.views_of(7, 7), [(0, (7, 7)), (2, (1, 1))]);
+        assert_eq!(samurai.value(0, 10), None);
+        assert_eq!(samurai.value(10, 7), Some(0));
+    }
+
+    #[test]
+    fn test_add_grid() {
+        let mut multi_grid = MultiGrid::new();
+        multi_grid.add_grid(Board::empty(), (0, 0));
+        let mut values = Array2::from_elem((9, 9), 0);
+        values[(0, 0)] = 5;
+        multi_grid.add_grid(Board::new(values), (6, 6));
+        // the number is copied to the first grid too
+        assert_eq!(multi_grid.grids()[0].values[(6, 6)], 5);
+        assert_eq!(multi_grid.value(6, 6), Some(5));
+        assert_eq!(multi_grid.canvas_shape(), (15, 15));
+    }
+
+    #[test]
+    #[should_panic(expected = "cell (6, 6) holds both 5 and 4")]
+    fn test_conflicting_grids() {
+        let mut multi_grid = MultiGrid::new();
+        let mut values = Array2::from_elem((9, 9), 0);
+        values[(6, 6)] = 5;
+        multi_grid.add_grid(Board::new(values.clone()), (0, 0));
+        values[(0, 0)] = 4;
+        multi_grid.add_grid(Board::new(values), (6, 6));
+    }
+
+    #[test]
+    fn test_from_file() {
+        let multi_grid = MultiGrid::from_board_dir("variants/quiz-samurai.txt");
+        assert_eq!(multi_grid.offsets(), Layout::Samurai.offsets(9));
+        assert_eq!(multi_grid.value(0, 4), Some(8));
+        assert_eq!(parse_grid("9 r1c13"), (9, (0, 12)));
+
+        // the digits of the file are not symbols of a letter alphabet
+        let path = utils::get_board_dir()
+            .unwrap()
+            .join("variants/quiz-samurai.txt");
+        let alphabet = Alphabet::new("ABCDEFGHI");
+        let multi_grid = MultiGrid::from_file_with_alphabet(path.to_str().unwrap(), alphabet);
+        assert_eq!(multi_grid.value(0, 4), Some(0));
+        assert_eq!(multi_grid.grids()[4].alphabet().symbol_of(1), 'A');
+    }
+
+    #[test]
+    fn test_samurai() {
+        let multi_grid = MultiGrid::from_board_dir("variants/quiz-samurai.txt");
+        let expected = MultiGrid::from_board_dir("variants/solution-samurai.txt");
+        let solved = multi_grid.solve().unwrap();
+        assert!(solved.is_complete());
+        assert!(solved.check_complete());
+        for (grid, expected_grid) in solved.grids().iter().zip(expected.grids()) {
+            assert_eq!(grid.values, expected_grid.values);
+        }
+        assert_eq!(multi_grid.count_solutions(2), 1);
+        // an empty canvas has many solutions
+        assert_eq!(
+            MultiGrid::with_layout(Layout::Twodoku, 4).count_solutions(2),
+            2
+        );
+    }
+
+    #[test]
+    fn test_rules_of_the_grids() {
+        // the diagonal of the first grid goes through the shared box
+        let mut twodoku = MultiGrid::with_layout(Layout::Twodoku, 4);
+        twodoku.grids[0].set_diagonal(true);
+        let solved = twodoku.solve().unwrap();
+        // the diagonals are among the units `check_complete` goes through
+        assert!(solved.grids()[0].is_diagonal());
+        assert!(solved.check_complete());
+    }
+}
@@ -0,0 +1,53 @@
+use ndarray::prelude::*;
+
+use crate::Constraint;
+
+/// the parity a cell of an even/odd sudoku is restricted to
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Parity {
+    Even,
+    Odd,
+}
+
+impl Parity {
+    /// whether the number has this parity
+    pub fn allows(&self, number: u8) -> bool {
+        match self {
+            Parity::Even => number.is_multiple_of(2),
+            Parity::Odd => !number.is_multiple_of(2),
+        }
+    }
+}
+
+/// a cell restricted to a parity
+pub(crate) type ParityCell = ((usize, usize), Parity);
+
+impl Constraint for ParityCell {
+    fn check(&self, values: &Array2<u8>) -> bool {
+        let &(pos, parity) = self;
+        values[pos] == 0 || parity.allows(values[pos])
+    }
+
+    fn prune(&self, candidates: &Array2<Vec<bool>>) -> Vec<((usize, usize), Vec<bool>)> {
+        let &(pos, parity) = self;
+        let possible = candidates[pos]
+            .iter()
+            .enumerate()
+            .map(|(ix, &x)| x && parity.allows(ix as u8 + 1))
+            .collect();
+        vec![(pos, possible)]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_allows() {
+        assert!(Parity::Even.allows(4));
+        assert!(!Parity::Even.allows(9));
+        assert!(Parity::Odd.allows(1));
+        assert!(!Parity::Odd.allows(16));
+    }
+}
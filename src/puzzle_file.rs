@@ -0,0 +1,397 @@
+use ndarray::prelude::*;
+use serde::Deserialize;
+use std::cmp::Ordering;
+
+use crate::{
+    box_regions, box_shape_of, parse_grid, parse_regions, try_parse_box_shape, try_parse_cell,
+    try_parse_direction, Alphabet, Arrow, Board, Cage, Dot, Edge, LittleKiller, Parity, Thermo, Xv,
+};
+
+/// the whole description of a puzzle
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct PuzzleFile {
+    /// rows (and columns) of the grid, it can be left out when there are givens
+    size: Option<usize>,
+    /// shape of the boxes written as `RxC`, guessed from the size when left out
+    boxes: Option<String>,
+    /// one line per row and one character per cell, as in board files
+    #[serde(default)]
+    givens: Vec<String>,
+    #[serde(default)]
+    constraints: Vec<Rule>,
+}
+
+/// a constraint of the puzzle, tagged by its `type`
+#[derive(Debug, Deserialize, PartialEq)]
+#[serde(tag = "type", rename_all = "kebab-case", deny_unknown_fields)]
+enum Rule {
+    /// regions replacing the boxes, one line per row and one character per cell
+    Regions {
+        rows: Vec<String>,
+    },
+    /// a group of cells that must contain every number once
+    ExtraRegion {
+        cells: Vec<String>,
+    },
+    LatinSquare,
+    Diagonal,
+    Hyper,
+    AntiKnight,
+    AntiKing,
+    NonConsecutive,
+    /// a `white` or `black` dot between two adjacent cells
+    Kropki {
+        color: String,
+        cells: [String; 2],
+    },
+    NegativeKropki,
+    /// a `x` or `v` marker between two adjacent cells
+    Xv {
+        marker: String,
+        cells: [String; 2],
+    },
+    NegativeXv,
+    /// a sign (`<` or `>`) comparing the first cell with the second one
+    Inequality {
+        cells: [String; 2],
+        sign: String,
+    },
+    Thermo {
+        cells: Vec<String>,
+    },
+    /// the sandwich clue of a row or a column, numbered from 1
+    Sandwich {
+        row: Option<usize>,
+        column: Option<usize>,
+        sum: u32,
+    },
+    Arrow {
+        circle: String,
+        cells: Vec<String>,
+    },
+    Even {
+        cells: Vec<String>,
+    },
+    Odd {
+        cells: Vec<String>,
+    },
+    Cage {
+        sum: u32,
+        cells: Vec<String>,
+    },
+    LittleKiller {
+        sum: u32,
+        start: String,
+        direction: String,
+    },
+}
+
+/// the board described in TOML, or why the description is not valid
+pub(crate) fn from_toml(contents: &str, alphabet: Alphabet) -> Result<Board, String> {
+    let puzzle: PuzzleFile = toml::from_str(contents)
+        .map_err(|error| format!("invalid puzzle description: {}", error))?;
+    puzzle.into_board(alphabet)
+}
+
+/// the board described in JSON, or why the description is not valid
+pub(crate) fn from_json(contents: &str, alphabet: Alphabet) -> Result<Board, String> {
+    let puzzle: PuzzleFile = serde_json::from_str(contents)
+        .map_err(|error| format!("invalid puzzle description: {}", error))?;
+    puzzle.into_board(alphabet)
+}
+
+impl PuzzleFile {
+    fn into_board(self, alphabet: Alphabet) -> Result<Board, String> {
+        let size = match (self.size, self.givens.len()) {
+            (None, 0) => return Err("the size of the grid is missing".to_string()),
+            (Some(size), 0) => size,
+            (None, rows) => rows,
+            (Some(size), rows) if size == rows => size,
+            (Some(size), rows) => {
+                return Err(format!(
+                    "there are {} rows of givens instead of {}",
+                    rows, size
+                ))
+            }
+        };
+        if alphabet.len() < size {
+            return Err(format!(
+                "an alphabet of {} symbols cannot write a {}x{} board",
+                alphabet.len(),
+                size,
+                size
+            ));
+        }
+        for c in self.givens.iter().flat_map(|line| line.chars()) {
+            if alphabet
+                .value_of(c)
+                .is_some_and(|value| value as usize > size)
+            {
+                return Err(format!(
+                    "{} is not a valid number on a {}x{} board",
+                    c, size, size
+                ));
+            }
+        }
+        let values = match self.givens.is_empty() {
+            true => Array2::from_elem((size, size), 0),
+            false => {
+                let lines: Vec<&str> = self.givens.iter().map(|line| line.as_str()).collect();
+                parse_grid(&lines, &alphabet)
+            }
+        };
+        let (box_rows, box_cols) = match &self.boxes {
+            Some(shape) => try_parse_box_shape(shape)
+                .filter(|&(rows, cols)| rows * cols == size)
+                .ok_or_else(|| {
+                    format!("{} is not a box shape of a {}x{} board", shape, size, size)
+                })?,
+            None => box_shape_of(size),
+        };
+        let mut regions = box_regions(size, box_rows, box_cols);
+        for rule in self.constraints.iter() {
+            if let Rule::Regions { rows } = rule {
+                let cells = rows.iter().map(|row| row.trim().chars().count());
+                if rows.len() != size || cells.into_iter().any(|cells| cells != size) {
+                    return Err(format!(
+                        "the regions must have {} rows of {} cells",
+                        size, size
+                    ));
+                }
+                regions = parse_regions(rows.iter().map(|row| row.as_str()).collect(), size);
+            }
+        }
+        let mut board = Board::new_with_regions(values, regions);
+        board.set_alphabet(alphabet);
+        for rule in self.constraints {
+            rule.apply(&mut board)?;
+        }
+        Ok(board)
+    }
+}
+
+impl Rule {
+    /// add the constraint to the board, or tell why it is not valid
+    fn apply(self, board: &mut Board) -> Result<(), String> {
+        let size = board.size();
+        match self {
+            Rule::Regions { .. } => (),
+            Rule::ExtraRegion { cells } => board.add_extra_region(parse_cells(&cells, size)?),
+            Rule::LatinSquare => board.set_latin_square(true),
+            Rule::Diagonal => board.set_diagonal(true),
+            Rule::Hyper => board.set_hyper(true),
+            Rule::AntiKnight => board.set_anti_knight(true),
+            Rule::AntiKing => board.set_anti_king(true),
+            Rule::NonConsecutive => board.set_non_consecutive(true),
+            Rule::Kropki { color, cells } => {
+                let dot = match color.as_str() {
+                    "white" => Dot::White,
+                    "black" => Dot::Black,
+                    _ => return Err(format!("{} is not a kropki dot", color)),
+                };
+                board.add_dot(parse_pair(&cells, size)?, dot);
+            }
+            Rule::NegativeKropki => board.set_negative_kropki(true),
+            Rule::Xv { marker, cells } => {
+                let xv = match marker.as_str() {
+                    "x" => Xv::X,
+                    "v" => Xv::V,
+                    _ => return Err(format!("{} is not a XV marker", marker)),
+                };
+                board.add_xv(parse_pair(&cells, size)?, xv);
+            }
+            Rule::NegativeXv => board.set_negative_xv(true),
+            Rule::Inequality { cells, sign } => {
+                let ordering = match sign.as_str() {
+                    "<" => Ordering::Less,
+                    ">" => Ordering::Greater,
+                    _ => return Err(format!("{} is not an inequality sign", sign)),
+                };
+                board.add_inequality(parse_pair(&cells, size)?, ordering);
+            }
+            Rule::Thermo { cells } => board.add_thermo(Thermo::new(parse_cells(&cells, size)?)),
+            Rule::Sandwich { row, column, sum } => match (row, column) {
+                (Some(row), None) if (1..=size).contains(&row) => {
+                    board.set_row_sandwich(row - 1, Some(sum))
+                }
+                (None, Some(col)) if (1..=size).contains(&col) => {
+                    board.set_column_sandwich(col - 1, Some(sum))
+                }
+                _ => {
+                    return Err(format!(
+                        "a sandwich clue needs either a row or a column from 1 to {}",
+                        size
+                    ))
+                }
+            },
+            Rule::Arrow { circle, cells } => board.add_arrow(Arrow::new(
+                parse_cell(&circle, size)?,
+                parse_cells(&cells, size)?,
+            )),
+            Rule::Even { cells } => {
+                for (row, col) in parse_cells(&cells, size)? {
+                    board.set_parity(row, col, Some(Parity::Even));
+                }
+            }
+            Rule::Odd { cells } => {
+                for (row, col) in parse_cells(&cells, size)? {
+                    board.set_parity(row, col, Some(Parity::Odd));
+                }
+            }
+            Rule::Cage { sum, cells } => board.add_cage(Cage::new(sum, parse_cells(&cells, size)?)),
+            Rule::LittleKiller {
+                sum,
+                start,
+                direction,
+            } => {
+                let direction = try_parse_direction(&direction)
+                    .ok_or_else(|| format!("{} is not a diagonal direction", direction))?;
+                board.add_little_killer(LittleKiller::new(
+                    sum,
+                    parse_cell(&start, size)?,
+                    direction,
+                ))
+            }
+        }
+        Ok(())
+    }
+}
+
+/// parse a cell of a board of `size` rows, e.g. `"r1c1"`
+fn parse_cell(cell: &str, size: usize) -> Result<(usize, usize), String> {
+    match try_parse_cell(cell) {
+        Some((row, col)) if row < size && col < size => Ok((row, col)),
+        Some(_) => Err(format!("{} is outside the board", cell)),
+        None => Err(format!("{} is not a valid cell", cell)),
+    }
+}
+
+/// parse cells written one per string, e.g. `["r1c1", "r1c2"]`
+fn parse_cells(cells: &[String], size: usize) -> Result<Vec<(usize, usize)>, String> {
+    cells.iter().map(|cell| parse_cell(cell, size)).collect()
+}
+
+/// parse two adjacent cells, e.g. `["r1c1", "r1c2"]`
+fn parse_pair(cells: &[String; 2], size: usize) -> Result<Edge, String> {
+    Ok((parse_cell(&cells[0], size)?, parse_cell(&cells[1], size)?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_toml() {
+        let board = from_toml(
+            r#"
+            size = 6
+            boxes = "3x2"
+
+            [[constraints]]
+            type = "diagonal"
+
+            [[constraints]]
+            type = "kropki"
+            color = "black"
+            cells = ["r1c1", "r1c2"]
+
+            [[constraints]]
+            type = "sandwich"
+            column = 2
+            sum = 5
+
+            [[constraints]]
+            type = "cage"
+            sum = 3
+            cells = ["r6c5", "r6c6"]
+            "#,
+            Alphabet::default(),
+        )
+        .unwrap();
+        assert_eq!(board.size(), 6);
+        // the boxes are 3 rows high
+        assert_eq!(board.regions[(2, 1)], 0);
+        assert_eq!(board.regions[(3, 0)], 3);
+        assert!(board.is_diagonal());
+        assert_eq!(board.dots(), [(((0, 0), (0, 1)), Dot::Black)]);
+        assert_eq!(board.column_sandwiches()[1], Some(5));
+        assert_eq!(board.cages(), [Cage::new(3, vec![(5, 4), (5, 5)])]);
+    }
+
+    #[test]
+    fn test_from_json() {
+        let board = from_json(
+            r#"{
+                "givens": ["1...", "..3.", "....", "...4"],
+                "constraints": [
+                    {"type": "anti-king"},
+                    {"type": "extra-region", "cells": ["r1c1", "r2c2", "r3c3", "r4c4"]}
+                ]
+            }"#,
+            Alphabet::default(),
+        )
+        .unwrap();
+        assert_eq!(board.values[(1, 2)], 3);
+        assert!(board.is_anti_king());
+        assert_eq!(board.extra_regions().len(), 1);
+    }
+
+    #[test]
+    fn test_files() {
+        // the same puzzles as the text files
+        let pairs = [
+            ("variants/quiz-thermo.toml", "variants/solution-thermo.txt"),
+            ("variants/quiz-killer.json", "variants/solution-killer.txt"),
+        ];
+        for (quiz, solution) in pairs {
+            let board = Board::from_board_dir(quiz);
+            let expected = Board::from_board_dir(solution);
+            assert_eq!(board.solve().unwrap().values, expected.values);
+        }
+    }
+
+    #[test]
+    fn test_unknown_constraint() {
+        let error = from_toml(
+            "size = 9\n[[constraints]]\ntype = \"chess\"\n",
+            Alphabet::default(),
+        );
+        assert!(matches!(error, Err(message) if message.starts_with("invalid puzzle description")));
+    }
+
+    #[test]
+    fn test_malformed_constraints() {
+        let cases = [
+            (
+                "type = \"sandwich\"\nsum = 5",
+                "needs either a row or a column",
+            ),
+            (
+                "type = \"sandwich\"\nrow = 10\nsum = 5",
+                "needs either a row or a column",
+            ),
+            (
+                "type = \"cage\"\nsum = 3\ncells = [\"r1c1\", \"a2\"]",
+                "a2 is not a valid cell",
+            ),
+            (
+                "type = \"thermo\"\ncells = [\"r1c1\", \"r1c10\"]",
+                "r1c10 is outside the board",
+            ),
+            (
+                "type = \"kropki\"\ncolor = \"red\"\ncells = [\"r1c1\", \"r1c2\"]",
+                "red is not a kropki dot",
+            ),
+        ];
+        for (constraint, expected) in cases {
+            let contents = format!("size = 9\n[[constraints]]\n{}\n", constraint);
+            match from_toml(&contents, Alphabet::default()) {
+                Ok(_) => panic!("{} should be refused", constraint),
+                Err(message) => assert!(message.contains(expected), "{}", message),
+            }
+        }
+        let error = from_json(r#"{"givens": ["1.", "3."]}"#, Alphabet::default());
+        assert!(matches!(error, Err(message) if message.contains("3 is not a valid number")));
+    }
+}
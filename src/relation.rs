@@ -0,0 +1,195 @@
+use ndarray::prelude::*;
+
+use crate::Constraint;
+
+/// two orthogonally adjacent cells, the first one is above or at the left of the second
+pub type Edge = ((usize, usize), (usize, usize));
+
+/// the dots of a Kropki sudoku, drawn on the edge between two cells
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Dot {
+    /// the numbers of the two cells are consecutive
+    White,
+    /// one number is the double of the other
+    Black,
+}
+
+impl Dot {
+    pub(crate) fn relation(&self) -> Relation {
+        match self {
+            Dot::White => Relation::Consecutive,
+            Dot::Black => Relation::Double,
+        }
+    }
+}
+
+/// the markers of a XV sudoku, drawn on the edge between two cells
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Xv {
+    /// the numbers of the two cells add up to 10
+    X,
+    /// the numbers of the two cells add up to 5
+    V,
+}
+
+impl Xv {
+    pub fn sum(&self) -> u32 {
+        match self {
+            Xv::X => 10,
+            Xv::V => 5,
+        }
+    }
+}
+
+/// two cells bound by a relation, in the order the relation is written for
+pub(crate) type Link = ((usize, usize), (usize, usize), Relation);
+
+impl Constraint for Link {
+    /// the relation can only fail once both cells are filled
+    fn check(&self, values: &Array2<u8>) -> bool {
+        let &(first, second, relation) = self;
+        let (first, second) = (values[first], values[second]);
+        first == 0 || second == 0 || relation.holds(first, second)
+    }
+
+    fn prune(&self, candidates: &Array2<Vec<bool>>) -> Vec<((usize, usize), Vec<bool>)> {
+        let &(first, second, relation) = self;
+        let (first_possible, second_possible) =
+            relation.possible_values(&candidates[first], &candidates[second]);
+        vec![(first, first_possible), (second, second_possible)]
+    }
+}
+
+/// a rule binding the numbers of two cells, written for the pair (first, second)
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) enum Relation {
+    /// the numbers are not consecutive
+    NonConsecutive,
+    /// the numbers are consecutive (white Kropki dot)
+    Consecutive,
+    /// one number is the double of the other (black Kropki dot)
+    Double,
+    /// the numbers are neither consecutive nor one the double of the other
+    /// (cells without a dot of a negative Kropki sudoku)
+    NoDot,
+    /// the first number is lower than the second (greater-than sudoku)
+    Less,
+    /// the numbers add up to neither 10 nor 5 (cells without a marker of a negative XV sudoku)
+    NoXv,
+}
+
+impl Relation {
+    /// whether `first` and `second` satisfy the relation
+    pub(crate) fn holds(&self, first: u8, second: u8) -> bool {
+        match self {
+            Relation::NonConsecutive => first.abs_diff(second) != 1,
+            Relation::Consecutive => first.abs_diff(second) == 1,
+            Relation::Double => first == 2 * second || second == 2 * first,
+            Relation::NoDot => {
+                !Relation::Consecutive.holds(first, second)
+                    && !Relation::Double.holds(first, second)
+            }
+            Relation::Less => first < second,
+            Relation::NoXv => {
+                let sum = (first + second) as u32;
+                sum != Xv::X.sum() && sum != Xv::V.sum()
+            }
+        }
+    }
+
+    /// the numbers of the first cell that have at least a number of the second one
+    /// satisfying the relation (`candidates` of each cell are indexed from 0 for 1), and the
+    /// same for the second cell
+    pub(crate) fn possible_values(
+        &self,
+        first: &[bool],
+        second: &[bool],
+    ) -> (Vec<bool>, Vec<bool>) {
+        let supported = |own: &[bool], other: &[bool], own_is_first: bool| -> Vec<bool> {
+            (0..own.len())
+                .map(|ix| {
+                    own[ix]
+                        && (0..other.len()).any(|other_ix| {
+                            let (a, b) = match own_is_first {
+                                true => (ix, other_ix),
+                                false => (other_ix, ix),
+                            };
+                            other[other_ix] && self.holds(a as u8 + 1, b as u8 + 1)
+                        })
+                })
+                .collect()
+        };
+        (
+            supported(first, second, true),
+            supported(second, first, false),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_non_consecutive() {
+        let relation = Relation::NonConsecutive;
+        assert!(relation.holds(1, 3));
+        assert!(!relation.holds(4, 5));
+        assert!(!relation.holds(5, 4));
+
+        // a 2 next to the cell rules out 1 and 3, which have no other option
+        let mut second = vec![false; 9];
+        second[1] = true;
+        let (first, second) = relation.possible_values(&[true; 9], &second);
+        assert_eq!(
+            first,
+            [false, true, false, true, true, true, true, true, true]
+        );
+        assert_eq!(second.iter().filter(|&&x| x).count(), 1);
+    }
+
+    #[test]
+    fn test_dots() {
+        assert!(Dot::White.relation().holds(3, 2));
+        assert!(!Dot::White.relation().holds(3, 5));
+        assert!(Dot::Black.relation().holds(3, 6));
+        assert!(Dot::Black.relation().holds(8, 4));
+        assert!(!Dot::Black.relation().holds(3, 5));
+        assert!(Relation::NoDot.holds(3, 5));
+        assert!(!Relation::NoDot.holds(1, 2));
+
+        // a black dot next to a 9 is impossible, next to 3 it leaves 6 (1.5 is not a number)
+        let mut second = vec![false; 9];
+        second[8] = true;
+        second[2] = true;
+        let (first, second) = Relation::Double.possible_values(&[true; 9], &second);
+        assert_eq!(first.iter().position(|&x| x), Some(5));
+        assert_eq!(first.iter().filter(|&&x| x).count(), 1);
+        assert!(!second[8]);
+    }
+
+    #[test]
+    fn test_less() {
+        assert!(Relation::Less.holds(2, 3));
+        assert!(!Relation::Less.holds(3, 2));
+
+        // the first cell cannot hold the highest number and the second the lowest
+        let (first, second) = Relation::Less.possible_values(&[true; 9], &[true; 9]);
+        assert!(!first[8] && first[..8].iter().all(|&x| x));
+        assert!(!second[0] && second[1..].iter().all(|&x| x));
+    }
+
+    #[test]
+    fn test_no_xv() {
+        assert!(Relation::NoXv.holds(3, 4));
+        assert!(!Relation::NoXv.holds(3, 7));
+        assert!(!Relation::NoXv.holds(4, 1));
+
+        // next to a 5 only another 5 adds up to 10 (0 would be needed for 5)
+        let mut second = vec![false; 9];
+        second[4] = true;
+        let (first, _) = Relation::NoXv.possible_values(&[true; 9], &second);
+        assert_eq!(first.iter().filter(|&&x| x).count(), 8);
+        assert!(!first[4]);
+    }
+}
@@ -0,0 +1,155 @@
+use ndarray::prelude::*;
+
+use crate::{sum, Constraint};
+
+/// the sandwich clue of a row or a column, `cells` are the cells of the line in order
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) struct Sandwich {
+    pub(crate) sum: u32,
+    pub(crate) cells: Vec<(usize, usize)>,
+}
+
+impl Constraint for Sandwich {
+    fn check(&self, values: &Array2<u8>) -> bool {
+        let line: Vec<u8> = self.cells.iter().map(|&pos| values[pos]).collect();
+        check(self.sum, &line)
+    }
+
+    fn prune(&self, candidates: &Array2<Vec<bool>>) -> Vec<((usize, usize), Vec<bool>)> {
+        let own: Vec<Vec<bool>> = self
+            .cells
+            .iter()
+            .map(|&pos| candidates[pos].clone())
+            .collect();
+        self.cells
+            .iter()
+            .copied()
+            .zip(possible_values(self.sum, &own))
+            .collect()
+    }
+
+    fn explain(&self) -> Option<String> {
+        Some(format!(
+            "the numbers between the lowest and the highest one add up to {}",
+            self.sum
+        ))
+    }
+}
+
+/// whether the numbers of a line can still add up to `sum` between its lowest and highest
+/// number (the 1 and the 9 of a 9x9 board), empty cells are 0. The sum is only checked once
+/// both ends are placed
+pub(crate) fn check(sum: u32, line: &[u8]) -> bool {
+    let size = line.len() as u8;
+    let ends = (
+        line.iter().position(|&n| n == 1),
+        line.iter().position(|&n| n == size),
+    );
+    let (first, last) = match ends {
+        (Some(low), Some(high)) => (low.min(high), low.max(high)),
+        _ => return true,
+    };
+    let between = &line[first + 1..last];
+    let total: u32 = between.iter().map(|&n| n as u32).sum();
+    match between.iter().all(|&n| n != 0) {
+        true => total == sum,
+        false => total <= sum,
+    }
+}
+
+/// given the numbers each cell of a line can still hold, returns the ones that are part of
+/// at least a placement of the lowest and highest number with different numbers adding up
+/// to `sum` between them
+pub(crate) fn possible_values(sum: u32, candidates: &[Vec<bool>]) -> Vec<Vec<bool>> {
+    let size = candidates.len();
+    let (low, high) = (0, size - 1);
+    let mut possible = vec![vec![false; size]; size];
+    // the numbers of the cells which are not the ends
+    let inner: Vec<Vec<bool>> = candidates
+        .iter()
+        .map(|cell| {
+            let mut cell = cell.clone();
+            cell[low] = false;
+            cell[high] = false;
+            cell
+        })
+        .collect();
+    for first in 0..size {
+        for last in first + 1..size {
+            for (first_end, last_end) in [(low, high), (high, low)] {
+                if !candidates[first][first_end] || !candidates[last][last_end] {
+                    continue;
+                }
+                // the cells outside the sandwich cannot be the ends
+                let mut outside = (0..first).chain(last + 1..size);
+                if outside.any(|cell| inner[cell].iter().all(|&x| !x)) {
+                    continue;
+                }
+                let between = match last - first - 1 {
+                    0 if sum == 0 => Vec::new(),
+                    0 => continue,
+                    _ => {
+                        let between = sum::possible_values(sum, &inner[first + 1..last], true);
+                        if between[0].iter().all(|&x| !x) {
+                            continue;
+                        }
+                        between
+                    }
+                };
+                possible[first][first_end] = true;
+                possible[last][last_end] = true;
+                for (cell, cell_possible) in possible.iter_mut().enumerate() {
+                    if cell == first || cell == last {
+                        continue;
+                    }
+                    let cell_inner = match first < cell && cell < last {
+                        true => &between[cell - first - 1],
+                        false => &inner[cell],
+                    };
+                    for (ix, &x) in cell_inner.iter().enumerate() {
+                        cell_possible[ix] |= x;
+                    }
+                }
+            }
+        }
+    }
+    possible
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_check() {
+        assert!(check(10, &[0; 9]));
+        assert!(check(10, &[1, 4, 6, 9, 0, 0, 0, 0, 0]));
+        assert!(!check(11, &[1, 4, 6, 9, 0, 0, 0, 0, 0]));
+        assert!(check(11, &[9, 4, 0, 1, 0, 0, 0, 0, 0]));
+        assert!(!check(3, &[9, 4, 0, 1, 0, 0, 0, 0, 0]));
+        assert!(check(0, &[9, 1, 0, 0, 0, 0, 0, 0, 0]));
+        assert!(!check(0, &[9, 2, 1, 0, 0, 0, 0, 0, 0]));
+    }
+
+    #[test]
+    fn test_possible_values() {
+        // 35 leaves the 1 and the 9 at the two ends of the line
+        let possible = possible_values(35, &vec![vec![true; 9]; 9]);
+        for end in [0, 8] {
+            let expected = [true, false, false, false, false, false, false, false, true];
+            assert_eq!(possible[end], expected);
+        }
+        assert!(possible[4][1..8].iter().all(|&x| x));
+        assert!(!possible[4][0] && !possible[4][8]);
+
+        // a 1 and a 9 next to each other
+        let possible = possible_values(0, &vec![vec![true; 4]; 4]);
+        assert!(possible.iter().all(|cell| cell.iter().all(|&x| x)));
+        let mut first = vec![false; 4];
+        first[0] = true;
+        let mut candidates = vec![vec![true; 4]; 4];
+        candidates[0] = first;
+        let possible = possible_values(0, &candidates);
+        assert_eq!(possible[1], [false, false, false, true]);
+    }
+}
@@ -0,0 +1,172 @@
+use ndarray::prelude::*;
+use std::collections::HashMap;
+
+use crate::Constraint;
+
+/// cells whose numbers must add up to `sum`, without repeating a number when `distinct`.
+/// Killer cages, XV pairs and little killer diagonals are all checked and pruned as sums
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) struct CellSum {
+    pub(crate) sum: u32,
+    pub(crate) cells: Vec<(usize, usize)>,
+    pub(crate) distinct: bool,
+}
+
+impl CellSum {
+    /// see `possible_values`, `candidates` are in the order of `cells`
+    pub(crate) fn possible_values(&self, candidates: &[Vec<bool>]) -> Vec<Vec<bool>> {
+        possible_values(self.sum, candidates, self.distinct)
+    }
+}
+
+impl Constraint for CellSum {
+    fn check(&self, values: &Array2<u8>) -> bool {
+        let numbers: Vec<u8> = self.cells.iter().map(|&pos| values[pos]).collect();
+        check(self.sum, &numbers, self.distinct)
+    }
+
+    fn prune(&self, candidates: &Array2<Vec<bool>>) -> Vec<((usize, usize), Vec<bool>)> {
+        let own: Vec<Vec<bool>> = self
+            .cells
+            .iter()
+            .map(|&pos| candidates[pos].clone())
+            .collect();
+        self.cells
+            .iter()
+            .copied()
+            .zip(self.possible_values(&own))
+            .collect()
+    }
+
+    fn explain(&self) -> Option<String> {
+        Some(format!(
+            "the numbers of {} cells add up to {}",
+            self.cells.len(),
+            self.sum
+        ))
+    }
+}
+
+/// partially filled cells (empty ones are 0) are valid if they can still add up to the sum,
+/// counting the empty cells as 1, and if their numbers are not repeated when `distinct`.
+/// Filled cells must match the sum exactly
+pub(crate) fn check(sum: u32, numbers: &[u8], distinct: bool) -> bool {
+    let mut elem_checked = vec![false; 256];
+    let mut total = 0;
+    let mut empty = 0;
+    for &elem in numbers {
+        if elem == 0 {
+            empty += 1;
+            continue;
+        } else if distinct && elem_checked[elem as usize] {
+            return false;
+        }
+        elem_checked[elem as usize] = true;
+        total += elem as u32;
+    }
+    match empty {
+        0 => total == sum,
+        _ => total + empty <= sum,
+    }
+}
+
+/// given the numbers each cell can still hold, returns the ones that are part of at least a
+/// way of filling the cells with numbers adding up to the sum (all different when `distinct`)
+pub(crate) fn possible_values(
+    sum: u32,
+    candidates: &[Vec<bool>],
+    distinct: bool,
+) -> Vec<Vec<bool>> {
+    let size = candidates[0].len();
+    assert!(
+        size <= 128,
+        "sums of boards with more than 128 numbers are not supported"
+    );
+    let mut possible = vec![vec![false; size]; candidates.len()];
+    let mut filler = Filler {
+        sum,
+        distinct,
+        candidates,
+        possible: &mut possible,
+        visited: HashMap::new(),
+    };
+    filler.fill(0, 0, 0);
+    possible
+}
+
+/// the search of the ways of filling some cells with numbers adding up to a sum
+struct Filler<'a> {
+    sum: u32,
+    distinct: bool,
+    candidates: &'a [Vec<bool>],
+    possible: &'a mut [Vec<bool>],
+    /// whether the rest of the cells can be filled from a cell, with the numbers in the
+    /// bit mask already used and a total reached
+    visited: HashMap<(usize, u128, u32), bool>,
+}
+
+impl Filler<'_> {
+    /// whether the cells from the `cell`-th on can be filled, when the numbers in the `used`
+    /// bit mask (only kept for distinct numbers, bit `n - 1` for `n`) adding up to `total` are
+    /// already in the previous cells. The numbers found to lead to a valid filling are marked
+    /// as possible
+    fn fill(&mut self, cell: usize, used: u128, total: u32) -> bool {
+        if cell == self.candidates.len() {
+            return total == self.sum;
+        }
+        if let Some(&can_be_filled) = self.visited.get(&(cell, used, total)) {
+            return can_be_filled;
+        }
+        let mut can_be_filled = false;
+        for ix in 0..self.candidates[cell].len() {
+            let n = ix as u32 + 1;
+            if !self.candidates[cell][ix] || used & (1 << ix) != 0 || total + n > self.sum {
+                continue;
+            }
+            let used = match self.distinct {
+                true => used | (1 << ix),
+                false => used,
+            };
+            if self.fill(cell + 1, used, total + n) {
+                self.possible[cell][ix] = true;
+                can_be_filled = true;
+            }
+        }
+        self.visited.insert((cell, used, total), can_be_filled);
+        can_be_filled
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_check() {
+        assert!(check(10, &[0, 0, 0], true));
+        assert!(check(10, &[4, 5, 0], true));
+        assert!(!check(10, &[4, 6, 0], true));
+        assert!(check(10, &[5, 5], false));
+        assert!(!check(10, &[5, 5], true));
+        assert!(!check(10, &[5, 4], false));
+    }
+
+    #[test]
+    fn test_possible_values() {
+        // 2 + 2 + 2 is only allowed with repeats
+        let candidates = vec![vec![true; 9]; 3];
+        let possible = possible_values(6, &candidates, true);
+        assert_eq!(possible[0].iter().filter(|&&x| x).count(), 3);
+        let possible = possible_values(6, &candidates, false);
+        assert_eq!(possible[0].iter().filter(|&&x| x).count(), 4);
+        let possible = possible_values(3, &candidates, false);
+        assert_eq!(possible[0].iter().position(|&x| x), Some(0));
+        assert_eq!(possible[0].iter().filter(|&&x| x).count(), 1);
+
+        // boards with more numbers than the bits of a u32
+        let candidates = vec![vec![true; 40]; 2];
+        let possible = possible_values(79, &candidates, true);
+        assert_eq!(possible[0].iter().position(|&x| x), Some(38));
+        assert_eq!(possible[0].iter().filter(|&&x| x).count(), 2);
+    }
+}
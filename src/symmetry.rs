@@ -0,0 +1,179 @@
+use std::{fmt, str::FromStr};
+
+use crate::Board;
+
+/// a symmetry of the pattern of the givens, each cell is given if and only if its images are
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Symmetry {
+    /// the pattern is the same after a half turn around the center
+    Rotational,
+    /// the pattern is mirrored across the horizontal axis, the top half onto the bottom one
+    Horizontal,
+    /// the pattern is mirrored across the vertical axis, the left half onto the right one
+    Vertical,
+    /// the pattern is mirrored across the main diagonal
+    Diagonal,
+    /// the pattern is mirrored across the anti-diagonal
+    AntiDiagonal,
+    /// all the symmetries above, along with the quarter turns
+    Dihedral,
+}
+
+impl Symmetry {
+    pub const ALL: [Symmetry; 6] = [
+        Symmetry::Rotational,
+        Symmetry::Horizontal,
+        Symmetry::Vertical,
+        Symmetry::Diagonal,
+        Symmetry::AntiDiagonal,
+        Symmetry::Dihedral,
+    ];
+
+    /// the cell and its images on a board with `size` rows, without repetitions
+    pub fn orbit(&self, (i, j): (usize, usize), size: usize) -> Vec<(usize, usize)> {
+        let n = size - 1;
+        let images = match self {
+            Symmetry::Rotational => vec![(i, j), (n - i, n - j)],
+            Symmetry::Horizontal => vec![(i, j), (n - i, j)],
+            Symmetry::Vertical => vec![(i, j), (i, n - j)],
+            Symmetry::Diagonal => vec![(i, j), (j, i)],
+            Symmetry::AntiDiagonal => vec![(i, j), (n - j, n - i)],
+            Symmetry::Dihedral => vec![
+                (i, j),
+                (j, n - i),
+                (n - i, n - j),
+                (n - j, i),
+                (n - i, j),
+                (i, n - j),
+                (j, i),
+                (n - j, n - i),
+            ],
+        };
+        let mut orbit = Vec::new();
+        for image in images {
+            if !orbit.contains(&image) {
+                orbit.push(image);
+            }
+        }
+        orbit
+    }
+}
+
+impl FromStr for Symmetry {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Symmetry::ALL
+            .into_iter()
+            .find(|symmetry| symmetry.to_string() == s.to_lowercase())
+            .ok_or_else(|| format!("{} is not a symmetry", s))
+    }
+}
+
+impl fmt::Display for Symmetry {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            Symmetry::Rotational => "rotational",
+            Symmetry::Horizontal => "horizontal",
+            Symmetry::Vertical => "vertical",
+            Symmetry::Diagonal => "diagonal",
+            Symmetry::AntiDiagonal => "anti-diagonal",
+            Symmetry::Dihedral => "dihedral",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+impl Board {
+    /// the symmetries of the pattern of the givens, an empty or a full board has all of them
+    pub fn symmetries(&self) -> Vec<Symmetry> {
+        let size = self.size();
+        Symmetry::ALL
+            .into_iter()
+            .filter(|symmetry| {
+                self.values.indexed_iter().all(|(pos, &value)| {
+                    symmetry
+                        .orbit(pos, size)
+                        .iter()
+                        .all(|&image| (self.values[image] > 0) == (value > 0))
+                })
+            })
+            .collect()
+    }
+
+    /// the empty cells grouped by their orbits, in the order of their first cell. Without a
+    /// symmetry each cell is an orbit on its own
+    pub(crate) fn empty_orbits(&self, symmetry: Option<Symmetry>) -> Vec<Vec<(usize, usize)>> {
+        let size = self.size();
+        let mut seen = Vec::new();
+        let mut orbits = Vec::new();
+        for (pos, _) in self.values.indexed_iter().filter(|(_, &value)| value == 0) {
+            if seen.contains(&pos) {
+                continue;
+            }
+            let orbit: Vec<(usize, usize)> = match symmetry {
+                Some(symmetry) => symmetry
+                    .orbit(pos, size)
+                    .into_iter()
+                    .filter(|&image| self.values[image] == 0)
+                    .collect(),
+                None => vec![pos],
+            };
+            seen.extend(orbit.iter().copied());
+            orbits.push(orbit);
+        }
+        orbits
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_orbit() {
+        assert_eq!(Symmetry::Rotational.orbit((0, 1), 9), [(0, 1), (8, 7)]);
+        assert_eq!(Symmetry::Rotational.orbit((4, 4), 9), [(4, 4)]);
+        assert_eq!(Symmetry::Horizontal.orbit((0, 1), 9), [(0, 1), (8, 1)]);
+        assert_eq!(Symmetry::Vertical.orbit((0, 1), 9), [(0, 1), (0, 7)]);
+        assert_eq!(Symmetry::Diagonal.orbit((0, 1), 9), [(0, 1), (1, 0)]);
+        assert_eq!(Symmetry::AntiDiagonal.orbit((0, 1), 9), [(0, 1), (7, 8)]);
+        assert_eq!(Symmetry::Dihedral.orbit((0, 1), 9).len(), 8);
+        assert_eq!(Symmetry::Dihedral.orbit((0, 0), 9).len(), 4);
+        assert_eq!("anti-diagonal".parse(), Ok(Symmetry::AntiDiagonal));
+    }
+
+    #[test]
+    fn test_symmetries() {
+        assert_eq!(Board::empty().symmetries(), Symmetry::ALL);
+        let mut board = Board::empty();
+        board.values[(0, 1)] = 1;
+        assert!(board.symmetries().is_empty());
+        board.values[(8, 7)] = 2;
+        assert_eq!(board.symmetries(), [Symmetry::Rotational]);
+        board.values[(0, 7)] = 3;
+        board.values[(8, 1)] = 4;
+        assert_eq!(
+            board.symmetries(),
+            [
+                Symmetry::Rotational,
+                Symmetry::Horizontal,
+                Symmetry::Vertical
+            ]
+        );
+        assert!(Board::from_board_dir("complete/quiz-00.txt")
+            .symmetries()
+            .is_empty());
+    }
+
+    #[test]
+    fn test_empty_orbits() {
+        let mut board = Board::empty_with_boxes(2, 2);
+        board.values[(3, 3)] = 1;
+        assert_eq!(board.empty_orbits(None).len(), 15);
+        let orbits = board.empty_orbits(Some(Symmetry::Rotational));
+        assert_eq!(orbits.len(), 8);
+        assert_eq!(orbits[0], [(0, 0)]);
+        assert_eq!(orbits[1], [(0, 1), (3, 2)]);
+    }
+}
@@ -0,0 +1,170 @@
+use ndarray::prelude::*;
+
+use crate::Constraint;
+
+/// a thermometer, the numbers strictly increase along its cells starting from the bulb
+#[derive(Clone, Debug, PartialEq)]
+pub struct Thermo {
+    cells: Vec<(usize, usize)>,
+}
+
+impl Thermo {
+    /// a thermometer made of `cells`, the first one is the bulb. Each cell must touch the
+    /// previous one, also diagonally
+    pub fn new(cells: Vec<(usize, usize)>) -> Self {
+        if cells.len() < 2 {
+            panic!("a thermometer must have at least two cells");
+        }
+        for (i, cell) in cells.iter().enumerate() {
+            if cells[..i].contains(cell) {
+                panic!("cell {:?} is repeated in the thermometer", cell);
+            }
+        }
+        for pair in cells.windows(2) {
+            let (prev, next) = (pair[0], pair[1]);
+            if prev.0.abs_diff(next.0) > 1 || prev.1.abs_diff(next.1) > 1 {
+                panic!("cells {:?} and {:?} are not adjacent", prev, next);
+            }
+        }
+        Thermo { cells }
+    }
+
+    pub fn cells(&self) -> &[(usize, usize)] {
+        &self.cells
+    }
+
+    /// given the numbers each cell of the thermometer can still hold (in the order of
+    /// `cells`), keeps only the ones between the lowest number allowed by the previous
+    /// cells and the highest one allowed by the next cells
+    pub(crate) fn possible_values(&self, candidates: &[Vec<bool>]) -> Vec<Vec<bool>> {
+        let size = candidates[0].len();
+        let len = candidates.len();
+        // lowest and highest index each cell can hold, `None` when it cannot hold any
+        let mut lowest: Vec<Option<usize>> = vec![None; len];
+        let mut highest: Vec<Option<usize>> = vec![None; len];
+        for cell in 0..len {
+            let from = match cell {
+                0 => 0,
+                _ => lowest[cell - 1].map_or(size, |ix| ix + 1),
+            };
+            lowest[cell] = (from..size).find(|&ix| candidates[cell][ix]);
+        }
+        for cell in (0..len).rev() {
+            let to = match cell + 1 == len {
+                true => size,
+                false => highest[cell + 1].unwrap_or(0),
+            };
+            highest[cell] = (0..to).rev().find(|&ix| candidates[cell][ix]);
+        }
+        candidates
+            .iter()
+            .enumerate()
+            .map(|(cell, cell_candidates)| {
+                (0..size)
+                    .map(|ix| {
+                        cell_candidates[ix]
+                            && lowest[cell].is_some_and(|lowest| lowest <= ix)
+                            && highest[cell].is_some_and(|highest| ix <= highest)
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+}
+
+impl Constraint for Thermo {
+    /// a partially filled thermometer is valid if its numbers increase and leave enough room
+    /// for the empty cells between them, before them and after them
+    fn check(&self, values: &Array2<u8>) -> bool {
+        let size = values.nrows();
+        let len = self.cells.len();
+        let filled: Vec<(usize, usize)> = self
+            .cells
+            .iter()
+            .enumerate()
+            .filter(|(_, &pos)| values[pos] != 0)
+            .map(|(ix, &pos)| (ix, values[pos] as usize))
+            .collect();
+        filled
+            .iter()
+            .all(|&(ix, n)| n > ix && n + (len - 1 - ix) <= size)
+            && filled
+                .windows(2)
+                .all(|pair| pair[1].1 >= pair[0].1 + (pair[1].0 - pair[0].0))
+    }
+
+    fn prune(&self, candidates: &Array2<Vec<bool>>) -> Vec<((usize, usize), Vec<bool>)> {
+        let own: Vec<Vec<bool>> = self
+            .cells
+            .iter()
+            .map(|&pos| candidates[pos].clone())
+            .collect();
+        self.cells
+            .iter()
+            .copied()
+            .zip(self.possible_values(&own))
+            .collect()
+    }
+
+    fn explain(&self) -> Option<String> {
+        Some("the numbers increase along the thermometer".to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_check() {
+        let thermo = Thermo::new(vec![(0, 0), (0, 1), (1, 2)]);
+        let mut values = Array2::from_elem((9, 9), 0_u8);
+        assert!(thermo.check(&values));
+        values[(0, 1)] = 5;
+        assert!(thermo.check(&values));
+        // no room for the bulb
+        values[(0, 1)] = 1;
+        assert!(!thermo.check(&values));
+        values[(0, 0)] = 2;
+        values[(0, 1)] = 3;
+        values[(1, 2)] = 9;
+        assert!(thermo.check(&values));
+        values[(1, 2)] = 3;
+        assert!(!thermo.check(&values));
+        // no room between the bulb and the tip
+        values[(0, 1)] = 0;
+        values[(1, 2)] = 3;
+        assert!(!thermo.check(&values));
+    }
+
+    #[test]
+    fn test_possible_values() {
+        let thermo = Thermo::new(vec![(0, 0), (0, 1), (0, 2)]);
+        let possible = thermo.possible_values(&vec![vec![true; 9]; 3]);
+        for (cell, cell_possible) in possible.iter().enumerate() {
+            let expected: Vec<bool> = (0..9).map(|ix| cell <= ix && ix < 7 + cell).collect();
+            assert_eq!(*cell_possible, expected);
+        }
+
+        // a 4 in the middle leaves 1-3 to the bulb and 5-9 to the tip
+        let mut middle = vec![false; 9];
+        middle[3] = true;
+        let possible = thermo.possible_values(&[vec![true; 9], middle, vec![true; 9]]);
+        assert_eq!(possible[0].iter().filter(|&&x| x).count(), 3);
+        assert_eq!(possible[2].iter().position(|&x| x), Some(4));
+
+        // nothing is left if the bulb cannot be lower than the tip
+        let mut high = vec![false; 9];
+        high[8] = true;
+        let mut low = vec![false; 9];
+        low[0] = true;
+        let possible = thermo.possible_values(&[high, vec![true; 9], low]);
+        assert!(possible.iter().all(|cell| cell.iter().all(|&x| !x)));
+    }
+
+    #[test]
+    #[should_panic(expected = "cells (0, 1) and (2, 1) are not adjacent")]
+    fn test_broken_thermo() {
+        Thermo::new(vec![(0, 0), (0, 1), (2, 1)]);
+    }
+}